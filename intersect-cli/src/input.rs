@@ -0,0 +1,245 @@
+use std::io::Read;
+use std::{fs, io};
+
+use intersections::Curve;
+use roxmltree::{Document, Node};
+use svgtypes::{Length, PointsParser, SimplePathSegment, SimplifyingPathParser, Transform};
+
+/// 圆弧近似为三次贝塞尔曲线时控制点的比例
+const KAPPA: f64 = 0.552_284_749_830_793_4;
+
+/// 读取曲线，`path` 为 `-` 时读取标准输入
+///
+/// 扩展名为 `.svg` 或内容以 `<` 开头时按 SVG 解析，否则按 JSON 解析
+pub fn read_curves(path: &str) -> Result<Vec<Curve>, String> {
+    let text = if path == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|err| format!("读取标准输入失败: {err}"))?;
+        text
+    } else {
+        fs::read_to_string(path).map_err(|err| format!("读取 {path} 失败: {err}"))?
+    };
+    let is_svg = path.to_ascii_lowercase().ends_with(".svg") || text.trim_start().starts_with('<');
+    if is_svg {
+        parse_svg(&text).map_err(|err| format!("解析 SVG {path} 失败: {err}"))
+    } else {
+        parse_json(&text).map_err(|err| format!("解析 JSON {path} 失败: {err}"))
+    }
+}
+
+/// 解析曲线数组 `[[p1x, p1y, c1x, c1y, c2x, c2y, p2x, p2y], ...]`，也接受长度为 8 的倍数的扁平数组
+pub fn parse_json(text: &str) -> Result<Vec<Curve>, String> {
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+    let items = value.as_array().ok_or("顶层必须是数组")?;
+    if items.iter().all(|item| item.is_number()) {
+        let values = numbers(items)?;
+        if values.len() % 8 != 0 {
+            return Err(format!("扁平数组长度 {} 不是 8 的倍数", values.len()));
+        }
+        return Ok(values
+            .chunks_exact(8)
+            .map(|chunk| chunk.try_into().unwrap())
+            .collect());
+    }
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let values = numbers(item.as_array().ok_or(format!("第 {i} 条曲线不是数组"))?)?;
+            values
+                .try_into()
+                .map_err(|values: Vec<f64>| format!("第 {i} 条曲线有 {} 个数值", values.len()))
+        })
+        .collect()
+}
+
+fn numbers(items: &[serde_json::Value]) -> Result<Vec<f64>, String> {
+    items
+        .iter()
+        .map(|item| item.as_f64().ok_or(format!("{item} 不是数值")))
+        .collect()
+}
+
+/// 解析 SVG 中的 `path`、`line`、`polyline`、`polygon`、`rect`、`circle`、`ellipse` 元素
+///
+/// 曲线顺序与文档顺序相同，元素及其祖先的 `transform` 会被应用，闭合路径会补上闭合线段
+pub fn parse_svg(text: &str) -> Result<Vec<Curve>, String> {
+    let document = Document::parse(text).map_err(|err| err.to_string())?;
+    let mut curves = vec![];
+    for node in document.descendants().filter(|node| node.is_element()) {
+        if node.ancestors().any(|node| {
+            matches!(
+                node.tag_name().name(),
+                "defs" | "clipPath" | "mask" | "symbol" | "pattern" | "marker"
+            )
+        }) {
+            continue;
+        }
+        let mut path = PathBuilder::new(transform(node)?);
+        match node.tag_name().name() {
+            "path" => {
+                for segment in SimplifyingPathParser::from(node.attribute("d").unwrap_or("")) {
+                    match segment.map_err(|err| err.to_string())? {
+                        SimplePathSegment::MoveTo { x, y } => path.move_to([x, y]),
+                        SimplePathSegment::LineTo { x, y } => path.line_to([x, y]),
+                        SimplePathSegment::CurveTo {
+                            x1,
+                            y1,
+                            x2,
+                            y2,
+                            x,
+                            y,
+                        } => path.cubic_to([x1, y1], [x2, y2], [x, y]),
+                        SimplePathSegment::Quadratic { x1, y1, x, y } => {
+                            path.quad_to([x1, y1], [x, y])
+                        }
+                        SimplePathSegment::ClosePath => path.close(),
+                    }
+                }
+            }
+            "line" => {
+                path.move_to([length(node, "x1")?, length(node, "y1")?]);
+                path.line_to([length(node, "x2")?, length(node, "y2")?]);
+            }
+            name @ ("polyline" | "polygon") => {
+                let points = PointsParser::from(node.attribute("points").unwrap_or(""));
+                for (i, (x, y)) in points.enumerate() {
+                    if i == 0 {
+                        path.move_to([x, y]);
+                    } else {
+                        path.line_to([x, y]);
+                    }
+                }
+                if name == "polygon" {
+                    path.close();
+                }
+            }
+            "rect" => {
+                let (x, y) = (length(node, "x")?, length(node, "y")?);
+                let (w, h) = (length(node, "width")?, length(node, "height")?);
+                path.move_to([x, y]);
+                path.line_to([x + w, y]);
+                path.line_to([x + w, y + h]);
+                path.line_to([x, y + h]);
+                path.close();
+            }
+            name @ ("circle" | "ellipse") => {
+                let (cx, cy) = (length(node, "cx")?, length(node, "cy")?);
+                let (rx, ry) = if name == "circle" {
+                    (length(node, "r")?, length(node, "r")?)
+                } else {
+                    (length(node, "rx")?, length(node, "ry")?)
+                };
+                let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+                path.move_to([cx + rx, cy]);
+                path.cubic_to([cx + rx, cy + ky], [cx + kx, cy + ry], [cx, cy + ry]);
+                path.cubic_to([cx - kx, cy + ry], [cx - rx, cy + ky], [cx - rx, cy]);
+                path.cubic_to([cx - rx, cy - ky], [cx - kx, cy - ry], [cx, cy - ry]);
+                path.cubic_to([cx + kx, cy - ry], [cx + rx, cy - ky], [cx + rx, cy]);
+            }
+            _ => continue,
+        }
+        curves.extend(path.curves);
+    }
+    Ok(curves)
+}
+
+/// 读取长度属性，缺省为 0
+fn length(node: Node, name: &str) -> Result<f64, String> {
+    match node.attribute(name) {
+        Some(value) => value
+            .parse::<Length>()
+            .map(|length| length.number)
+            .map_err(|err| format!("属性 {name}=\"{value}\": {err}")),
+        None => Ok(0.0),
+    }
+}
+
+/// 元素到根节点坐标系的变换
+fn transform(node: Node) -> Result<Transform, String> {
+    let mut result = Transform::default();
+    for node in node.ancestors().filter(|node| node.is_element()) {
+        if let Some(value) = node.attribute("transform") {
+            let ts = value
+                .parse::<Transform>()
+                .map_err(|err| format!("属性 transform=\"{value}\": {err}"))?;
+            result = multiply(&ts, &result);
+        }
+    }
+    Ok(result)
+}
+
+/// 先应用 `m2` 再应用 `m1` 的变换
+fn multiply(m1: &Transform, m2: &Transform) -> Transform {
+    Transform::new(
+        m1.a * m2.a + m1.c * m2.b,
+        m1.b * m2.a + m1.d * m2.b,
+        m1.a * m2.c + m1.c * m2.d,
+        m1.b * m2.c + m1.d * m2.d,
+        m1.a * m2.e + m1.c * m2.f + m1.e,
+        m1.b * m2.e + m1.d * m2.f + m1.f,
+    )
+}
+
+/// 将路径命令转换为曲线，直线段的控制点与端点重合，与 paper.js 的 `getValues` 一致
+struct PathBuilder {
+    transform: Transform,
+    start: [f64; 2],
+    current: [f64; 2],
+    curves: Vec<Curve>,
+}
+
+impl PathBuilder {
+    fn new(transform: Transform) -> Self {
+        PathBuilder {
+            transform,
+            start: [0.0, 0.0],
+            current: [0.0, 0.0],
+            curves: vec![],
+        }
+    }
+
+    fn apply(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        let ts = &self.transform;
+        [ts.a * x + ts.c * y + ts.e, ts.b * x + ts.d * y + ts.f]
+    }
+
+    fn move_to(&mut self, point: [f64; 2]) {
+        self.start = point;
+        self.current = point;
+    }
+
+    fn line_to(&mut self, point: [f64; 2]) {
+        self.cubic_to(self.current, point, point);
+    }
+
+    fn quad_to(&mut self, control: [f64; 2], point: [f64; 2]) {
+        let [x0, y0] = self.current;
+        let [cx, cy] = control;
+        let [x, y] = point;
+        self.cubic_to(
+            [x0 + (cx - x0) * 2.0 / 3.0, y0 + (cy - y0) * 2.0 / 3.0],
+            [x + (cx - x) * 2.0 / 3.0, y + (cy - y) * 2.0 / 3.0],
+            point,
+        );
+    }
+
+    fn cubic_to(&mut self, control1: [f64; 2], control2: [f64; 2], point: [f64; 2]) {
+        let [p1x, p1y] = self.apply(self.current);
+        let [c1x, c1y] = self.apply(control1);
+        let [c2x, c2y] = self.apply(control2);
+        let [p2x, p2y] = self.apply(point);
+        self.curves.push([p1x, p1y, c1x, c1y, c2x, c2y, p2x, p2y]);
+        self.current = point;
+    }
+
+    /// 闭合路径，终点与起点不重合时补上闭合线段
+    fn close(&mut self) {
+        if self.current != self.start {
+            self.line_to(self.start);
+        }
+        self.current = self.start;
+    }
+}
@@ -0,0 +1,338 @@
+//! 批量求交命令行工具，用于在浏览器外复现问题
+//!
+//! 只给出一个文件时求曲线集合的自相交，给出两个文件时求两组曲线之间的相交
+mod input;
+mod output;
+
+use std::collections::HashSet;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use std::{env, fs, io};
+
+use intersections::{
+    get_intersections_with_stats, min_distance, refine_locations, render_debug_svg, Broadphase,
+    ClosestPoints, CollisionDetection, Curve, DoubleDouble, EndpointPolicy, Float, Stats,
+};
+use output::{Format, Row};
+
+const USAGE: &str = "\
+用法: intersect-cli [选项] <曲线文件> [曲线文件2]
+
+曲线文件为 8 个数值一组的 JSON 数组或 SVG 文件，`-` 表示标准输入。
+只给出一个文件时求自相交，给出两个文件时求两组曲线之间的相交。
+
+选项:
+  -f, --format <json|csv>        输出格式，默认 json
+  -p, --policy <include-all|exclude-shared|exclude-all>
+                                 端点接触的处理方式，默认 exclude-shared
+      --precision <f64|f32|dd>   计算精度，各精度使用各自的几何容差，默认 f64
+  -a, --accuracy <距离>          对结果做牛顿迭代修正直到残差不超过该距离，并输出残差、参数误差上界和迭代状态
+      --tolerance <距离>         另外输出距离不超过该值但没有相交点的曲线对，给出最近的一对点和距离，
+                                 与相交点一样受端点处理方式约束
+  -t, --timing                   在标准错误输出中打印耗时和求交过程的统计数据
+  -r, --repeat <次数>            重复求交的次数，用于统计耗时，默认 1
+  -o, --output <文件>            写入文件而不是标准输出
+      --svg <文件>               另外输出调试用的 SVG，包含曲线、边界框、候选曲线对和相交点
+  -h, --help                     显示帮助";
+
+/// 计算精度
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Precision {
+    F32,
+    F64,
+    DoubleDouble,
+}
+
+struct Options {
+    inputs: Vec<String>,
+    format: Format,
+    policy: EndpointPolicy,
+    precision: Precision,
+    accuracy: Option<f64>,
+    tolerance: Option<f64>,
+    timing: bool,
+    repeat: usize,
+    output: Option<String>,
+    svg: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        inputs: vec![],
+        format: Format::Json,
+        policy: EndpointPolicy::default(),
+        precision: Precision::F64,
+        accuracy: None,
+        tolerance: None,
+        timing: false,
+        repeat: 1,
+        output: None,
+        svg: None,
+    };
+    while let Some(arg) = args.next() {
+        // 同时支持 `--name value` 和 `--name=value`
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.into())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or(format!("{name} 缺少参数"))
+        };
+        match name.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-f" | "--format" => {
+                options.format = match value()?.as_str() {
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    other => return Err(format!("未知的输出格式 {other}")),
+                }
+            }
+            "-p" | "--policy" => {
+                options.policy = match value()?.as_str() {
+                    "include-all" => EndpointPolicy::IncludeAll,
+                    "exclude-shared" => EndpointPolicy::ExcludeShared,
+                    "exclude-all" => EndpointPolicy::ExcludeAll,
+                    other => return Err(format!("未知的端点处理方式 {other}")),
+                }
+            }
+            "--precision" => {
+                options.precision = match value()?.as_str() {
+                    "f32" => Precision::F32,
+                    "f64" => Precision::F64,
+                    "dd" => Precision::DoubleDouble,
+                    other => return Err(format!("未知的计算精度 {other}")),
+                }
+            }
+            "-a" | "--accuracy" => {
+                let text = value()?;
+                match text.parse::<f64>() {
+                    Ok(accuracy) if accuracy > 0.0 => options.accuracy = Some(accuracy),
+                    _ => return Err(format!("修正精度必须是正数: {text}")),
+                }
+            }
+            "--tolerance" => {
+                let text = value()?;
+                match text.parse::<f64>() {
+                    Ok(tolerance) if tolerance >= 0.0 => options.tolerance = Some(tolerance),
+                    _ => return Err(format!("容差必须是非负数: {text}")),
+                }
+            }
+            "-t" | "--timing" => options.timing = true,
+            "-r" | "--repeat" => {
+                let text = value()?;
+                match text.parse::<usize>() {
+                    Ok(repeat) if repeat > 0 => options.repeat = repeat,
+                    _ => return Err(format!("重复次数必须是正整数: {text}")),
+                }
+            }
+            "-o" | "--output" => options.output = Some(value()?),
+            "--svg" => options.svg = Some(value()?),
+            _ if name.starts_with('-') && name != "-" => return Err(format!("未知的选项 {name}")),
+            _ => options.inputs.push(arg),
+        }
+    }
+    if options.inputs.is_empty() || options.inputs.len() > 2 {
+        return Err("需要一个或两个曲线文件".into());
+    }
+    Ok(Some(options))
+}
+
+/// 以精度 `F` 求交，返回结果、统计数据和每次求交的耗时
+fn intersect<F: Float>(
+    curves1: &[Curve],
+    curves2: &[Curve],
+    is_self: bool,
+    options: &Options,
+) -> (Vec<Row>, Stats, Vec<Duration>) {
+    let convert = |curves: &[Curve]| -> Vec<Curve<F>> {
+        curves.iter().map(|curve| curve.map(F::from_f64)).collect()
+    };
+    let (curves1, curves2) = (convert(curves1), convert(curves2));
+    let mut rows = vec![];
+    let mut stats = Stats::default();
+    let mut durations = vec![];
+    for _ in 0..options.repeat {
+        let start = Instant::now();
+        let mut locations = vec![];
+        stats = get_intersections_with_stats(
+            &curves1,
+            &curves2,
+            is_self,
+            options.policy,
+            &mut locations,
+        );
+        rows = match options.accuracy {
+            Some(accuracy) => refine_locations(
+                &curves1,
+                &curves2,
+                is_self,
+                &locations,
+                F::from_f64(accuracy),
+            )
+            .into_iter()
+            .map(|refined| Row {
+                location: refined.location.map(F::to_f64),
+                residual: Some(refined.residual.to_f64()),
+                error_bound: Some(refined.error_bound.to_f64()),
+                status: Some(refined.status),
+                ..Row::default()
+            })
+            .collect(),
+            None => locations
+                .iter()
+                .map(|location| Row {
+                    location: location.map(F::to_f64),
+                    ..Row::default()
+                })
+                .collect(),
+        };
+        if let Some(tolerance) = options.tolerance {
+            let tolerance = F::from_f64(tolerance);
+            let near = near_misses(&curves1, &curves2, is_self, &locations, tolerance);
+            rows.extend(near.into_iter().filter_map(|closest| {
+                let [t1, i1, x1, y1, t2, i2, x2, y2, distance] = closest.to_array().map(F::to_f64);
+                let location = [t1, i1, x1, y1, t2, i2, x2, y2];
+                options.policy.keeps(&location).then_some(Row {
+                    location,
+                    distance: Some(distance),
+                    ..Row::default()
+                })
+            }));
+        }
+        durations.push(start.elapsed());
+    }
+    (rows, stats, durations)
+}
+
+/// 距离不超过 `tolerance` 但没有相交点的曲线对，按曲线序号排列
+fn near_misses<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    is_self: bool,
+    locations: &[[F; 8]],
+    tolerance: F,
+) -> Vec<ClosestPoints<F>> {
+    let intersecting: HashSet<(u32, u32)> = locations
+        .iter()
+        .map(|location| (location[1].to_f64() as u32, location[5].to_f64() as u32))
+        .collect();
+    let collisions = CollisionDetection::find_curve_bounds_collisions(
+        curves1,
+        curves2,
+        is_self,
+        tolerance,
+        Broadphase::default(),
+    );
+    collisions
+        .pairs()
+        .filter(|&(i, j)| (!is_self || j > i) && !intersecting.contains(&(i, j)))
+        .filter_map(|(i, j)| {
+            let closest = min_distance(&curves1[i as usize], &curves2[j as usize]);
+            (closest.distance <= tolerance).then_some(ClosestPoints {
+                curve1: i,
+                curve2: j,
+                ..closest
+            })
+        })
+        .collect()
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let start = Instant::now();
+    let curves1 = input::read_curves(&options.inputs[0])?;
+    let curves2 = match options.inputs.get(1) {
+        Some(path) => input::read_curves(path)?,
+        None => curves1.clone(),
+    };
+    let read_time = start.elapsed();
+    let is_self = options.inputs.len() == 1;
+
+    let (rows, stats, durations) = match options.precision {
+        Precision::F32 => intersect::<f32>(&curves1, &curves2, is_self, &options),
+        Precision::F64 => intersect::<f64>(&curves1, &curves2, is_self, &options),
+        Precision::DoubleDouble => intersect::<DoubleDouble>(&curves1, &curves2, is_self, &options),
+    };
+
+    let written = match &options.output {
+        Some(path) => fs::File::create(path)
+            .and_then(|mut file| output::write_rows(&mut file, &rows, is_self, options.format)),
+        None => output::write_rows(&mut io::stdout().lock(), &rows, is_self, options.format),
+    };
+    match written {
+        // 输出被 `head` 等命令提前关闭时正常退出
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => {
+            return Err(format!("写入结果失败: {err}"))
+        }
+        _ => {}
+    }
+
+    if let Some(path) = &options.svg {
+        let locations: Vec<_> = rows.iter().map(|row| row.location).collect();
+        let svg = render_debug_svg(&curves1, &curves2, is_self, &locations);
+        fs::write(path, svg).map_err(|err| format!("写入 {path} 失败: {err}"))?;
+    }
+
+    if options.timing {
+        let total: Duration = durations.iter().sum();
+        let min = durations.iter().min().copied().unwrap_or_default();
+        let max = durations.iter().max().copied().unwrap_or_default();
+        if is_self {
+            eprintln!("曲线数量: {}", curves1.len());
+        } else {
+            eprintln!("曲线数量: {} / {}", curves1.len(), curves2.len());
+        }
+        let near = rows.iter().filter(|row| row.distance.is_some()).count();
+        eprintln!("相交点数量: {}", rows.len() - near);
+        if options.tolerance.is_some() {
+            eprintln!("接近但不相交: {near}");
+        }
+        eprintln!("读取耗时: {:.3}ms", milliseconds(read_time));
+        eprintln!(
+            "求交耗时: 平均 {:.3}ms，最小 {:.3}ms，最大 {:.3}ms（{} 次）",
+            milliseconds(total) / durations.len() as f64,
+            milliseconds(min),
+            milliseconds(max),
+            durations.len()
+        );
+        eprintln!(
+            "候选曲线对: {}，有相交点: {}",
+            stats.candidate_pairs, stats.intersecting_pairs
+        );
+        eprintln!(
+            "分支: 直线与直线 {}，直线与曲线 {}，曲线与曲线 {}",
+            stats.line_line, stats.line_curve, stats.curve_curve
+        );
+        eprintln!(
+            "贝塞尔裁剪: 共 {} 次，单对最多 {} 次，最大深度 {}",
+            stats.clip_calls, stats.max_clip_calls, stats.max_recursion
+        );
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => match run(options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("错误: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("错误: {err}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
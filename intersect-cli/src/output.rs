@@ -0,0 +1,116 @@
+use std::io::{self, Write};
+
+use intersections::{Intersection, RefineStatus};
+use serde::Serialize;
+
+/// 输出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+/// 一个相交点，开启牛顿迭代修正时附带残差、参数误差上界和迭代状态，
+/// 接近但不相交的曲线对附带两条曲线之间的距离
+#[derive(Default)]
+pub struct Row {
+    pub location: [f64; 8],
+    pub residual: Option<f64>,
+    pub error_bound: Option<f64>,
+    pub status: Option<RefineStatus>,
+    pub distance: Option<f64>,
+}
+
+/// JSON 输出的相交点对象，在库的 `Intersection` 上附加修正和距离信息
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Object {
+    #[serde(flatten)]
+    intersection: Intersection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    residual: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_bound: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<RefineStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance: Option<f64>,
+}
+
+/// 枚举值序列化后的名称，与 JSON 输出一致
+fn name(value: &impl Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+/// 可选的数值，缺少时为空
+fn optional(value: Option<f64>) -> String {
+    value.map_or(String::new(), |value| value.to_string())
+}
+
+/// 写出相交点，JSON 对象的字段与 wasm 的 `rust_get_intersection_objects` 相同
+pub fn write_rows(
+    out: &mut impl Write,
+    rows: &[Row],
+    is_self: bool,
+    format: Format,
+) -> io::Result<()> {
+    match format {
+        Format::Json => {
+            let objects: Vec<_> = rows
+                .iter()
+                .map(|row| Object {
+                    intersection: Intersection::from_location(&row.location, is_self),
+                    residual: row.residual,
+                    error_bound: row.error_bound,
+                    status: row.status,
+                    distance: row.distance,
+                })
+                .collect();
+            serde_json::to_writer_pretty(&mut *out, &objects)?;
+            writeln!(out)
+        }
+        Format::Csv => {
+            let refined = rows.iter().any(|row| row.residual.is_some());
+            let near = rows.iter().any(|row| row.distance.is_some());
+            write!(out, "curve1,t1,x1,y1,curve2,t2,x2,y2,kind")?;
+            if refined {
+                write!(out, ",residual,errorBound,status")?;
+            }
+            if near {
+                write!(out, ",distance")?;
+            }
+            writeln!(out)?;
+            for row in rows {
+                let item = Intersection::from_location(&row.location, is_self);
+                let [x1, y1] = item.point1;
+                let [x2, y2] = item.point2;
+                write!(
+                    out,
+                    "{},{},{x1},{y1},{},{},{x2},{y2},{}",
+                    item.curve1,
+                    item.t1,
+                    item.curve2,
+                    item.t2,
+                    name(&item.kind)
+                )?;
+                if refined {
+                    write!(
+                        out,
+                        ",{},{},{}",
+                        optional(row.residual),
+                        optional(row.error_bound),
+                        row.status.as_ref().map_or(String::new(), name)
+                    )?;
+                }
+                if near {
+                    write!(out, ",{}", optional(row.distance))?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+    }
+}
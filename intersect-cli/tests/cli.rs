@@ -0,0 +1,147 @@
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_intersect-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // 子进程可能在写入完成前就已退出，结果由输出判断
+    if let Err(err) = child.stdin.take().unwrap().write_all(stdin.as_bytes()) {
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_json_input() {
+    let lines = "[
+        [38.5, 0, 38.5, 0, 62, 87, 62, 87],
+        [0, 64.5, 0, 64.5, 80, 17.5, 80, 17.5],
+        [80, 17.5, 80, 17.5, 0, 17.5, 0, 17.5],
+        [0, 17.5, 0, 17.5, 78.5, 67, 78.5, 67],
+        [78.5, 67, 78.5, 67, 0, 64.5, 0, 64.5]
+    ]";
+    let output = run(&["-", "--timing"], lines);
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = value.as_array().unwrap();
+    assert_eq!(items.len(), 5);
+    assert!(items.iter().all(|item| item["kind"] == "interior"));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("相交点数量: 5"));
+
+    // 后四条直线首尾相连，保留端点接触
+    let output = run(&["-", "-f", "csv", "--policy=include-all"], lines);
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert!(csv.starts_with("curve1,t1,x1,y1,curve2,t2,x2,y2,kind\n"));
+    assert_eq!(
+        csv.lines()
+            .filter(|line| line.ends_with(",sharedEndpoint"))
+            .count(),
+        4
+    );
+}
+
+#[test]
+fn test_svg_input() {
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+        <defs><path d="M 0 50 H 100" /></defs>
+        <g transform="translate(10 0)">
+            <path d="M 0 0 L 20 20" />
+        </g>
+        <rect x="0" y="0" width="20" height="20" />
+    </svg>"#;
+    // 测试并行运行，文件名带上进程号和测试名避免冲突
+    let debug = std::env::temp_dir().join(format!(
+        "intersect-cli-{}-svg-input.svg",
+        std::process::id()
+    ));
+    let output = run(
+        &[
+            "-",
+            "-f",
+            "csv",
+            "-a",
+            "1e-9",
+            "--svg",
+            debug.to_str().unwrap(),
+        ],
+        svg,
+    );
+    assert!(output.status.success());
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = csv
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').collect())
+        .collect();
+    // 平移后的对角线与矩形上边和右边各相交一次
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[0][2], rows[0][3]), ("10", "0"));
+    assert_eq!((rows[1][2], rows[1][3]), ("20", "10"));
+    assert_eq!(rows[0].len(), 12);
+    // 交点在对角线的端点上，控制点与端点重合处导数为零
+    assert_eq!(rows[0][11], "singular");
+    let content = std::fs::read_to_string(&debug).unwrap();
+    std::fs::remove_file(debug).unwrap();
+    let debug = content;
+    assert_eq!(debug.matches("<path").count(), 5);
+    assert_eq!(debug.matches("<circle").count(), 2);
+
+    let output = run(&["-", "--format", "xml"], svg);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_tolerance() {
+    // 第二条曲线的最低点在第一条直线上方 0.5 处，第三条直线的端点在第一条直线下方 0.25 处
+    let lines = "[
+        [0, 0, 0, 0, 10, 0, 10, 0],
+        [0, 3.5, 3, -0.5, 7, -0.5, 10, 3.5],
+        [5, -1, 5, -1, 5, -0.25, 5, -0.25]
+    ]";
+    let output = run(&["-"], lines);
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(value.as_array().unwrap().is_empty());
+
+    let output = run(&["-", "--tolerance", "0.6", "--timing"], lines);
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = value.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(
+        (&items[0]["curve1"], &items[0]["curve2"]),
+        (&0.into(), &1.into())
+    );
+    assert!((items[0]["distance"].as_f64().unwrap() - 0.5).abs() < 1e-6);
+    assert_eq!(items[0]["kind"], "interior");
+    assert_eq!(
+        (&items[1]["curve1"], &items[1]["curve2"]),
+        (&0.into(), &2.into())
+    );
+    assert!((items[1]["distance"].as_f64().unwrap() - 0.25).abs() < 1e-9);
+    assert_eq!(items[1]["kind"], "endpoint");
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("接近但不相交: 2"));
+
+    // 排除所有端点接触时不报告端点处的接近
+    let output = run(
+        &["-", "-f", "csv", "--tolerance=0.3", "-p", "exclude-all"],
+        lines,
+    );
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(csv, "curve1,t1,x1,y1,curve2,t2,x2,y2,kind\n");
+    let output = run(&["-", "-f", "csv", "--tolerance=0.3"], lines);
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert!(csv.starts_with("curve1,t1,x1,y1,curve2,t2,x2,y2,kind,distance\n"));
+    assert_eq!(csv.lines().count(), 2);
+
+    let output = run(&["-", "--tolerance", "-1"], lines);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,71 @@
+//! 比较扫描和层次包围盒在不同分布的边界框上的耗时
+//!
+//! `cargo bench --bench broadphase`
+use std::hint::black_box;
+use std::time::Instant;
+
+use intersections::{Broadphase, CollisionDetection};
+
+const COUNT: usize = 5_000;
+
+fn layouts() -> Vec<(&'static str, Vec<[f64; 4]>)> {
+    let mut seed = 1u32;
+    let mut random = |scale: f64| {
+        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (seed >> 8) as f64 / (1u32 << 24) as f64 * scale
+    };
+    let mut scattered = vec![];
+    let mut timeline = vec![];
+    let mut text_runs = vec![];
+    let mut l_shape = vec![];
+    for i in 0..COUNT {
+        let (x, y) = (random(1000.0), random(1000.0));
+        scattered.push([x, y, x + 10.0, y + 10.0]);
+        // 竖直排列的时间线
+        let (x, y) = (random(20.0), i as f64 * 12.0);
+        timeline.push([x, y, x + 80.0, y + 10.0]);
+        // 横跨整个页面的文字行
+        let y = i as f64 * 12.0 + random(2.0);
+        text_runs.push([0.0, y, 1000.0, y + 10.0]);
+        let (x, y) = (random(20.0), i as f64 * 12.0);
+        l_shape.push(if i % 2 == 0 {
+            [x, y, x + 10.0, y + 10.0]
+        } else {
+            [y, x, y + 10.0, x + 10.0]
+        });
+    }
+    vec![
+        ("scattered", scattered),
+        ("timeline", timeline),
+        ("text_runs", text_runs),
+        ("l_shape", l_shape),
+    ]
+}
+
+fn main() {
+    const ROUNDS: u32 = 20;
+    for (name, bounds) in layouts() {
+        for broadphase in [Broadphase::SweepAndPrune, Broadphase::Bvh] {
+            let mut pairs = 0;
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                let collisions = CollisionDetection::find_bounds_collisions(
+                    black_box(&bounds),
+                    black_box(&bounds),
+                    true,
+                    0.0,
+                    broadphase,
+                );
+                pairs = collisions.indices.len();
+            }
+            let elapsed = start.elapsed();
+            println!(
+                "{:<10} {:<14} {:>6} pairs {:>10.3} us/iter",
+                name,
+                format!("{broadphase:?}"),
+                pairs,
+                elapsed.as_secs_f64() * 1e6 / ROUNDS as f64
+            );
+        }
+    }
+}
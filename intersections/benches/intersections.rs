@@ -0,0 +1,71 @@
+//! 在测试用例的输入上测量求交耗时
+//!
+//! `cargo bench --bench intersections`
+use std::hint::black_box;
+use std::time::Instant;
+
+fn curve_sets() -> Vec<(&'static str, Vec<[f64; 8]>)> {
+    vec![
+        (
+            "lines",
+            vec![
+                [38.5, 0.0, 38.5, 0.0, 62.0, 87.0, 62.0, 87.0],
+                [0.0, 64.5, 0.0, 64.5, 80.0, 17.5, 80.0, 17.5],
+                [80.0, 17.5, 80.0, 17.5, 0.0, 17.5, 0.0, 17.5],
+                [0.0, 17.5, 0.0, 17.5, 78.5, 67.0, 78.5, 67.0],
+                [78.5, 67.0, 78.5, 67.0, 0.0, 64.5, 0.0, 64.5],
+            ],
+        ),
+        (
+            "line_and_curve",
+            vec![
+                [
+                    1.0, 131.842, 72.3227, 8.75809, 110.937, -81.0265, 203.5, 117.342,
+                ],
+                [
+                    203.5, 117.342, 101.207, 207.919, 64.5398, 183.033, 1.0, 131.842,
+                ],
+                [18.0, 251.5, 18.0, 251.5, 227.0, 90.0, 227.0, 90.0],
+                [227.0, 90.0, 227.0, 90.0, 2.0, 74.0, 2.0, 74.0],
+                [2.0, 74.0, 2.0, 74.0, 202.0, 251.5, 202.0, 251.5],
+                [202.0, 251.5, 202.0, 251.5, 154.5, 2.0, 154.5, 2.0],
+                [154.5, 2.0, 154.5, 2.0, 18.0, 251.5, 18.0, 251.5],
+            ],
+        ),
+        (
+            "curves",
+            vec![
+                [100.0, 50.0, 100.0, 22.385763, 22.385763, 100.0, 50.0, 100.0],
+                [100.0, 50.0, 100.0, 22.385763, 0.0, 22.385763, 0.0, 50.0],
+                [100.0, 50.0, 100.0, 77.614237, 22.385763, 0.0, 50.0, 0.0],
+                [50.0, 100.0, 77.614237, 100.0, 0.0, 22.385763, 0.0, 50.0],
+                [50.0, 100.0, 22.385763, 100.0, 77.614237, 0.0, 50.0, 0.0],
+                [0.0, 50.0, 0.0, 77.614237, 77.614237, 0.0, 50.0, 0.0],
+            ],
+        ),
+    ]
+}
+
+fn main() {
+    const ROUNDS: u32 = 20_000;
+    for (name, curves) in curve_sets() {
+        let mut locations = Vec::new();
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            locations.clear();
+            intersections::get_intersections(
+                black_box(&curves),
+                black_box(&curves),
+                true,
+                &mut locations,
+            );
+        }
+        let elapsed = start.elapsed();
+        println!(
+            "{:<16} {:>4} locations {:>10.3} us/iter",
+            name,
+            locations.len(),
+            elapsed.as_secs_f64() * 1e6 / ROUNDS as f64
+        );
+    }
+}
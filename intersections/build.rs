@@ -0,0 +1,23 @@
+//! 启用 `ffi` 特性时在 `OUT_DIR` 中生成 C 头文件 `intersections.h`
+//!
+//! 提交的 `include/intersections.h` 需要显式更新：`INTERSECTIONS_UPDATE_HEADER=1 cargo build --features ffi`，
+//! 测试 `test_ffi_header` 检查它与生成结果一致
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-env-changed=INTERSECTIONS_UPDATE_HEADER");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let bindings = cbindgen::generate(&crate_dir).expect("生成 C 头文件失败");
+    bindings.write_to_file(format!("{}/intersections.h", out_dir));
+    if std::env::var_os("INTERSECTIONS_UPDATE_HEADER").is_some() {
+        bindings.write_to_file(format!("{}/include/intersections.h", crate_dir));
+    }
+}
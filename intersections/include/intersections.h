@@ -0,0 +1,151 @@
+/* 由 cbindgen 生成，请勿手动修改 */
+
+#ifndef INTERSECTIONS_H
+#define INTERSECTIONS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * 保留所有端点接触
+ */
+#define INTERSECTIONS_INCLUDE_ALL 0
+
+/**
+ * 排除同时位于两条曲线端点上的接触
+ */
+#define INTERSECTIONS_EXCLUDE_SHARED 1
+
+/**
+ * 排除位于任一曲线端点上的接触
+ */
+#define INTERSECTIONS_EXCLUDE_ALL 2
+
+/**
+ * 成功
+ */
+#define INTERSECTIONS_OK 0
+
+/**
+ * `policy` 不是 `INTERSECTIONS_INCLUDE_ALL`、`INTERSECTIONS_EXCLUDE_SHARED` 或 `INTERSECTIONS_EXCLUDE_ALL`
+ */
+#define INTERSECTIONS_ERROR_INVALID_POLICY 1
+
+/**
+ * 相交点结果，`data` 中每 8 个数为一个相交点 `[t1, i1, x1, y1, t2, i2, x2, y2]`，`len` 为相交点数量
+ */
+typedef struct {
+  double *data;
+  size_t len;
+} IntersectionsLocations;
+
+/**
+ * 边界碰撞结果，第 i 条曲线可能相交的曲线序号为 `indices[offsets[i]..offsets[i + 1]]`
+ */
+typedef struct {
+  uint32_t *offsets;
+  size_t offsets_len;
+  uint32_t *indices;
+  size_t indices_len;
+} IntersectionsCollisions;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * 求两组曲线的相交点写入 `out`，`is_self` 时忽略 `curves2`
+ *
+ * 失败时 `out` 为空结果，不需要释放
+ *
+ * # Safety
+ *
+ * `curves1`、`curves2` 分别指向 `len1 * 8`、`len2 * 8` 个有效的 `double`，`out` 指向可写的
+ * `IntersectionsLocations`
+ */
+int32_t intersections_get(const double *curves1,
+                          size_t len1,
+                          const double *curves2,
+                          size_t len2,
+                          bool is_self,
+                          uint32_t policy,
+                          IntersectionsLocations *out);
+
+/**
+ * 求交并写入调用方提供的缓冲区，最多写入 `capacity` 个相交点，相交点总数写入 `total`
+ *
+ * # Safety
+ *
+ * 与 `intersections_get` 相同，`out` 指向 `capacity * 8` 个可写的 `double`，`total` 指向可写的 `size_t`
+ */
+int32_t intersections_get_into(const double *curves1,
+                               size_t len1,
+                               const double *curves2,
+                               size_t len2,
+                               bool is_self,
+                               uint32_t policy,
+                               double *out,
+                               size_t capacity,
+                               size_t *total);
+
+/**
+ * 释放 `intersections_get` 返回的结果
+ *
+ * # Safety
+ *
+ * `locations` 必须由 `intersections_get` 返回且只释放一次
+ */
+void intersections_locations_free(IntersectionsLocations locations);
+
+/**
+ * 切割曲线 `[t1, t2]` 部分，结果写入 `out`
+ *
+ * # Safety
+ *
+ * `v` 指向 8 个有效的 `double`，`out` 指向 8 个可写的 `double`
+ */
+void intersections_split_cubic_bezier_part(const double *v, double t1, double t2, double *out);
+
+/**
+ * 计算曲线上的点、切线、法线或曲率，`type_` 含义与 `evaluate` 相同，`t` 超出 `[0, 1]` 时返回 false
+ *
+ * # Safety
+ *
+ * `v` 指向 8 个有效的 `double`，`out` 指向 2 个可写的 `double`
+ */
+bool intersections_evaluate(const double *v,
+                            double t,
+                            uint8_t type_,
+                            double *out);
+
+/**
+ * 检测两组曲线之间的边界碰撞，`is_self` 时忽略 `curves2`
+ *
+ * # Safety
+ *
+ * 与 `intersections_get` 相同
+ */
+IntersectionsCollisions intersections_find_curve_bounds_collisions(const double *curves1,
+                                                                   size_t len1,
+                                                                   const double *curves2,
+                                                                   size_t len2,
+                                                                   bool is_self,
+                                                                   double tolerance);
+
+/**
+ * 释放 `intersections_find_curve_bounds_collisions` 返回的结果
+ *
+ * # Safety
+ *
+ * `collisions` 必须由 `intersections_find_curve_bounds_collisions` 返回且只释放一次
+ */
+void intersections_collisions_free(IntersectionsCollisions collisions);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* INTERSECTIONS_H */
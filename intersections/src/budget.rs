@@ -0,0 +1,176 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::collision_detection::CollisionDetection;
+use crate::{intersect_from, Bvh, Curve, EndpointPolicy, Float, Stats};
+
+/// 每完成多少个单位的工作读取一次时钟
+const CLOCK_INTERVAL: u32 = 256;
+
+/// 求交的进度，指向下一步要处理的曲线 `curve` 及其第 `step` 步
+///
+/// 第 0 步是查询曲线的候选曲线以及曲线自交，第 k 步是与第 k 个候选曲线的求交
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IntersectionCursor {
+    pub curve: u32,
+    pub step: u32,
+}
+
+/// 可以分段执行的求交任务
+///
+/// 构建时只为第二组曲线的边界框建立层次包围盒，耗时为 O(n log n)。
+/// 每条曲线的候选曲线在处理到它时才查询，查询计入预算，曲线对再多也不会在一次调用中全部展开。
+/// 每次传入的曲线必须与构建时相同，各次追加的结果依次拼接后与 `get_intersections_with_policy` 相同
+#[derive(Clone, Debug)]
+pub struct IntersectionJob<F = f64> {
+    is_self: bool,
+    policy: EndpointPolicy,
+    bvh: Bvh<F>,
+    candidates: Vec<u32>,
+    cursor: Option<IntersectionCursor>,
+    stats: Stats,
+}
+
+impl<F: Float> IntersectionJob<F> {
+    pub fn new(
+        curves1: &[Curve<F>],
+        curves2: &[Curve<F>],
+        is_self: bool,
+        policy: EndpointPolicy,
+    ) -> Self {
+        let curves2 = if is_self { curves1 } else { curves2 };
+        IntersectionJob {
+            is_self,
+            policy,
+            bvh: Bvh::new(&CollisionDetection::get_bounds(curves2)),
+            candidates: vec![],
+            cursor: Some(IntersectionCursor::default()),
+            stats: Stats::default(),
+        }
+    }
+
+    /// 继续求交，新的相交点追加到 `locations`，全部完成时返回 true
+    ///
+    /// 只在曲线对之间暂停，每次调用至少完成一步，已完成时不做任何事
+    pub fn resume<B: Budget>(
+        &mut self,
+        curves1: &[Curve<F>],
+        curves2: &[Curve<F>],
+        budget: &mut B,
+        locations: &mut Vec<[F; 8]>,
+    ) -> bool {
+        let curves2 = if self.is_self { curves1 } else { curves2 };
+        debug_assert_eq!(curves2.len(), self.bvh.len());
+        if let Some(cursor) = self.cursor {
+            self.cursor = intersect_from(
+                curves1,
+                curves2,
+                self.is_self,
+                self.policy,
+                &self.bvh,
+                &mut self.candidates,
+                cursor,
+                budget,
+                &mut self.stats,
+                locations,
+            );
+        }
+        self.cursor.is_none()
+    }
+
+    /// 下一步的位置，全部完成时为 None
+    pub fn cursor(&self) -> Option<IntersectionCursor> {
+        self.cursor
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cursor.is_none()
+    }
+
+    /// 到目前为止累计的统计数据
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+/// 求交的工作量预算，每条曲线的候选查询记 1 个单位，一个候选曲线对记 1 个单位，贝塞尔裁剪每次另记 1 个单位
+pub trait Budget {
+    /// 完成了 `work` 个单位的工作，返回 true 表示应当暂停
+    fn spend(&mut self, work: u32) -> bool;
+}
+
+/// 不限制工作量
+impl Budget for () {
+    #[inline(always)]
+    fn spend(&mut self, _: u32) -> bool {
+        false
+    }
+}
+
+impl<B: Budget> Budget for Option<B> {
+    fn spend(&mut self, work: u32) -> bool {
+        self.as_mut().is_some_and(|budget| budget.spend(work))
+    }
+}
+
+/// 任意一个预算用完时暂停
+impl<A: Budget, B: Budget> Budget for (A, B) {
+    fn spend(&mut self, work: u32) -> bool {
+        let a = self.0.spend(work);
+        let b = self.1.spend(work);
+        a || b
+    }
+}
+
+/// 完成 `max_work` 个单位的工作后暂停
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WorkBudget {
+    pub max_work: u32,
+    pub used: u32,
+}
+
+impl WorkBudget {
+    pub fn new(max_work: u32) -> Self {
+        WorkBudget { max_work, used: 0 }
+    }
+}
+
+impl Budget for WorkBudget {
+    fn spend(&mut self, work: u32) -> bool {
+        self.used = self.used.saturating_add(work);
+        self.used >= self.max_work
+    }
+}
+
+/// 时钟 `clock` 达到 `deadline` 后暂停，时钟每隔一段工作量读取一次
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline<C> {
+    clock: C,
+    deadline: f64,
+    pending: u32,
+}
+
+impl<C: FnMut() -> f64> Deadline<C> {
+    pub fn new(clock: C, deadline: f64) -> Self {
+        Deadline {
+            clock,
+            deadline,
+            pending: 0,
+        }
+    }
+}
+
+impl<C: FnMut() -> f64> Budget for Deadline<C> {
+    fn spend(&mut self, work: u32) -> bool {
+        self.pending = self.pending.saturating_add(work);
+        if self.pending < CLOCK_INTERVAL {
+            return false;
+        }
+        self.pending = 0;
+        (self.clock)() >= self.deadline
+    }
+}
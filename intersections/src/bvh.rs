@@ -0,0 +1,150 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::Float;
+
+/// 叶子节点最多包含的边界框数量
+const LEAF_SIZE: usize = 4;
+
+/// 节点的边界框，叶子节点的 `count` 大于 0，内部节点的左子节点紧跟在自身之后
+#[derive(Clone, Copy, Debug)]
+struct Node<F> {
+    bounds: [F; 4],
+    start: u32,
+    count: u32,
+    right: u32,
+}
+
+/// 边界框的层次包围盒
+///
+/// 每个节点按中心点在分布更宽的坐标轴上对半划分，与扫描不同，查询的耗时不受边界框在某个方向上聚集的影响。
+/// 构建一次后可以反复查询，坐标含 NaN 的边界框不会被查到
+#[derive(Clone, Debug)]
+pub struct Bvh<F = f64> {
+    bounds: Vec<[F; 4]>,
+    indices: Vec<u32>,
+    nodes: Vec<Node<F>>,
+}
+
+fn union<F: Float>(a: [F; 4], b: &[F; 4]) -> [F; 4] {
+    [
+        a[0].min(b[0]),
+        a[1].min(b[1]),
+        a[2].max(b[2]),
+        a[3].max(b[3]),
+    ]
+}
+
+fn center<F: Float>(b: &[F; 4], axis: usize) -> F {
+    b[axis] + b[axis + 2]
+}
+
+impl<F: Float> Bvh<F> {
+    /// 由 `[min_x, min_y, max_x, max_y]` 格式的边界框构建，查询结果为边界框的序号
+    pub fn new(bounds: &[[F; 4]]) -> Self {
+        let mut bvh = Bvh {
+            bounds: bounds.to_vec(),
+            indices: (0..bounds.len() as u32)
+                .filter(|&i| !bounds[i as usize].iter().any(|value| value.is_nan()))
+                .collect(),
+            nodes: vec![],
+        };
+        if !bvh.indices.is_empty() {
+            bvh.build(0, bvh.indices.len());
+        }
+        bvh
+    }
+
+    /// 为 `indices[start..end]` 建立节点，返回节点的序号
+    fn build(&mut self, start: usize, end: usize) -> u32 {
+        let empty = [F::INFINITY, F::INFINITY, F::NEG_INFINITY, F::NEG_INFINITY];
+        let (mut bounds, mut centers) = (empty, empty);
+        for &i in &self.indices[start..end] {
+            let b = &self.bounds[i as usize];
+            bounds = union(bounds, b);
+            let c = [center(b, 0), center(b, 1), center(b, 0), center(b, 1)];
+            centers = union(centers, &c);
+        }
+        let node = self.nodes.len() as u32;
+        self.nodes.push(Node {
+            bounds,
+            start: start as u32,
+            count: (end - start) as u32,
+            right: 0,
+        });
+        let axis = if centers[2] - centers[0] >= centers[3] - centers[1] {
+            0
+        } else {
+            1
+        };
+        // 中心点重合时无法划分，留作叶子节点
+        if end - start <= LEAF_SIZE || centers[axis + 2] <= centers[axis] {
+            return node;
+        }
+
+        let mid = (start + end) / 2;
+        let all_bounds = &self.bounds;
+        self.indices[start..end].select_nth_unstable_by(mid - start, |&i, &j| {
+            center(&all_bounds[i as usize], axis)
+                .partial_cmp(&center(&all_bounds[j as usize], axis))
+                .unwrap_or(Ordering::Equal)
+        });
+        self.build(start, mid);
+        let right = self.build(mid, end);
+        self.nodes[node as usize].count = 0;
+        self.nodes[node as usize].right = right;
+        node
+    }
+
+    /// 边界框的数量
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    /// 依次访问与 `rect` 的间隙不超过 `tolerance` 的边界框序号，顺序不固定
+    pub fn query(&self, rect: [F; 4], tolerance: F, visit: impl FnMut(u32)) {
+        self.traverse(
+            |b| {
+                b[0] <= rect[2] + tolerance
+                    && b[2] >= rect[0] - tolerance
+                    && b[1] <= rect[3] + tolerance
+                    && b[3] >= rect[1] - tolerance
+            },
+            visit,
+        );
+    }
+
+    /// 依次访问满足 `overlaps` 的边界框序号，不满足的节点整个跳过，顺序不固定
+    pub(crate) fn traverse(
+        &self,
+        mut overlaps: impl FnMut(&[F; 4]) -> bool,
+        mut visit: impl FnMut(u32),
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index as usize];
+            if !overlaps(&node.bounds) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.right);
+                stack.push(index + 1);
+                continue;
+            }
+            let (start, end) = (node.start as usize, (node.start + node.count) as usize);
+            for &i in &self.indices[start..end] {
+                if overlaps(&self.bounds[i as usize]) {
+                    visit(i);
+                }
+            }
+        }
+    }
+}
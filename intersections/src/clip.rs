@@ -0,0 +1,303 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::collision_detection::CollisionDetection;
+use crate::{
+    evaluate, is_close, line_and_curve_intersection, split_cubic_bezier_part, Curve, Float,
+};
+
+/// 半平面 `p[axis] <= value`（`keep_less`）或 `p[axis] >= value`
+#[derive(Clone, Copy, Debug)]
+struct HalfPlane<F> {
+    axis: usize,
+    value: F,
+    keep_less: bool,
+}
+
+/// 直线表示为控制点与端点重合的曲线
+fn line<F: Float>(p: [F; 2], q: [F; 2]) -> Curve<F> {
+    [p[0], p[1], p[0], p[1], q[0], q[1], q[0], q[1]]
+}
+
+impl<F: Float> HalfPlane<F> {
+    fn contains(&self, p: [F; 2]) -> bool {
+        if self.keep_less {
+            p[self.axis] <= self.value
+        } else {
+            p[self.axis] >= self.value
+        }
+    }
+
+    /// 曲线穿过边界的参数，按从小到大排列，不含端点
+    fn crossings(&self, v: &Curve<F>) -> Vec<F> {
+        // 边界线覆盖曲线在另一个坐标轴上的范围即可
+        let bounds = CollisionDetection::get_curve_bounds(v);
+        let other = 1 - self.axis;
+        let (lo, hi) = (bounds[other] - F::ONE, bounds[other + 2] + F::ONE);
+        let edge = if self.axis == 0 {
+            [self.value, lo, self.value, hi]
+        } else {
+            [lo, self.value, hi, self.value]
+        };
+        let mut ts: Vec<F> = line_and_curve_intersection(v, &edge)
+            .into_iter()
+            .map(|(t, ..)| t)
+            .filter(|&t| t > F::CURVETIME_EPSILON && t < F::ONE - F::CURVETIME_EPSILON)
+            .collect();
+        ts.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+        ts.dedup_by(|a, b| *a - *b <= F::CURVETIME_EPSILON);
+        ts
+    }
+
+    /// 把一串首尾相接的曲线裁剪为位于半平面内的若干段，相邻曲线在内部相接时属于同一段
+    ///
+    /// 返回的第二个值表示第一段是否从起点开始、最后一段是否到终点结束
+    fn clip_run(&self, run: &[Curve<F>]) -> (Vec<Vec<Curve<F>>>, bool, bool) {
+        let mut runs: Vec<Vec<Curve<F>>> = vec![];
+        let (mut starts_inside, mut inside) = (false, false);
+        for (k, v) in run.iter().enumerate() {
+            let ts = self.crossings(v);
+            // 两侧都在半平面内的根是切点，合并相邻的区间
+            let mut intervals: Vec<(F, F, bool)> = vec![];
+            let mut start = F::ZERO;
+            for end in ts.iter().copied().chain(core::iter::once(F::ONE)) {
+                let mid = evaluate(v, (start + end) / F::from_f64(2.0), 0);
+                let keep = mid.is_some_and(|p| self.contains(p));
+                match intervals.last_mut() {
+                    Some(last) if last.2 == keep => last.1 = end,
+                    _ => intervals.push((start, end, keep)),
+                }
+                start = end;
+            }
+            for (i, &(t1, t2, keep)) in intervals.iter().enumerate() {
+                if k == 0 && i == 0 {
+                    starts_inside = keep;
+                }
+                if keep {
+                    let mut piece = split_cubic_bezier_part(v, t1, t2);
+                    // 在边界上切开的端点精确落在边界上
+                    if t1 > F::ZERO {
+                        piece[self.axis] = self.value;
+                    }
+                    if t2 < F::ONE {
+                        piece[6 + self.axis] = self.value;
+                    }
+                    match runs.last_mut() {
+                        Some(last) if inside => last.push(piece),
+                        _ => runs.push(vec![piece]),
+                    }
+                }
+                inside = keep;
+            }
+        }
+        (runs, starts_inside, inside)
+    }
+
+    /// 裁剪闭合路径，离开和重新进入半平面的两点之间沿边界补上直线
+    fn clip_ring(&self, ring: &[Curve<F>]) -> Vec<Curve<F>> {
+        let (mut runs, starts_inside, ends_inside) = self.clip_run(ring);
+        if runs.len() > 1 && starts_inside && ends_inside {
+            let first = runs.remove(0);
+            runs.last_mut().unwrap().extend(first);
+        }
+        let mut result = vec![];
+        for (k, run) in runs.iter().enumerate() {
+            result.extend_from_slice(run);
+            let end = run[run.len() - 1];
+            let next = runs[(k + 1) % runs.len()][0];
+            if !is_close(end[6], end[7], next[0], next[1]) {
+                result.push(line([end[6], end[7]], [next[0], next[1]]));
+            }
+        }
+        // 只在边界上接触时剩下的路径完全落在边界上，不包围任何区域
+        if result
+            .iter()
+            .all(|v| (0..4).all(|k| v[k * 2 + self.axis] == self.value))
+        {
+            return vec![];
+        }
+        result
+    }
+}
+
+/// 按端点把曲线分成首尾相接的若干串，闭合时不相接的首尾补上直线
+pub(crate) fn chains<F: Float>(curves: &[Curve<F>], closed: bool) -> Vec<Vec<Curve<F>>> {
+    let mut chains: Vec<Vec<Curve<F>>> = vec![];
+    for v in curves {
+        match chains.last_mut() {
+            Some(chain)
+                if {
+                    let last = chain[chain.len() - 1];
+                    is_close(last[6], last[7], v[0], v[1])
+                } =>
+            {
+                chain.push(*v)
+            }
+            _ => chains.push(vec![*v]),
+        }
+    }
+    if closed {
+        for chain in chains.iter_mut() {
+            let (first, last) = (chain[0], chain[chain.len() - 1]);
+            if !is_close(last[6], last[7], first[0], first[1]) {
+                chain.push(line([last[6], last[7]], [first[0], first[1]]));
+            }
+        }
+    }
+    chains
+}
+
+/// 区间 `[min, max]` 有效，端点为 NaN 时无效
+fn ordered<F: Float>(min: F, max: F) -> bool {
+    min <= max
+}
+
+/// 把路径裁剪到坐标轴 `axis` 上的 `[min, max]` 之间
+fn clip_axis<F: Float>(
+    paths: &[Vec<Curve<F>>],
+    axis: usize,
+    min: F,
+    max: F,
+    closed: bool,
+) -> Vec<Vec<Curve<F>>> {
+    let mut paths = paths.to_vec();
+    for (value, keep_less) in [(min, false), (max, true)] {
+        let plane = HalfPlane {
+            axis,
+            value,
+            keep_less,
+        };
+        paths = if closed {
+            paths
+                .iter()
+                .map(|ring| plane.clip_ring(ring))
+                .filter(|ring| !ring.is_empty())
+                .collect()
+        } else {
+            paths.iter().flat_map(|run| plane.clip_run(run).0).collect()
+        };
+    }
+    paths
+}
+
+fn clip_chains<F: Float>(
+    paths: &[Vec<Curve<F>>],
+    rect: [F; 4],
+    closed: bool,
+) -> Vec<Vec<Curve<F>>> {
+    let [min_x, min_y, max_x, max_y] = rect;
+    if !ordered(min_x, max_x) || !ordered(min_y, max_y) {
+        return vec![];
+    }
+    let paths = clip_axis(paths, 0, min_x, max_x, closed);
+    clip_axis(&paths, 1, min_y, max_y, closed)
+}
+
+/// 把曲线裁剪到矩形 `rect = [min_x, min_y, max_x, max_y]` 内，返回首尾相接的若干段路径
+///
+/// 部分在矩形内的曲线在边界处精确切开。`closed` 时把每串首尾相接的曲线看作闭合路径，
+/// 在矩形外的部分由边界上的直线代替，裁剪后的路径仍然闭合，填充结果不变
+pub fn clip_to_rect<F: Float>(
+    curves: &[Curve<F>],
+    rect: [F; 4],
+    closed: bool,
+) -> Vec<Vec<Curve<F>>> {
+    clip_chains(&chains(curves, closed), rect, closed)
+}
+
+/// 均匀划分的网格，第 `column` 列第 `row` 行的瓦片左上角为 `origin + [column, row] * tile_size`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TileGrid<F = f64> {
+    pub origin: [F; 2],
+    pub tile_size: [F; 2],
+    pub columns: u32,
+    pub rows: u32,
+}
+
+impl<F: Float> TileGrid<F> {
+    /// 瓦片的矩形 `[min_x, min_y, max_x, max_y]`
+    pub fn tile_rect(&self, column: u32, row: u32) -> [F; 4] {
+        let x = self.origin[0] + self.tile_size[0] * F::from_f64(column as f64);
+        let y = self.origin[1] + self.tile_size[1] * F::from_f64(row as f64);
+        [x, y, x + self.tile_size[0], y + self.tile_size[1]]
+    }
+
+    /// 与 `[min, max]` 重叠的瓦片序号范围，`axis` 为 0 时是列，为 1 时是行
+    fn range(&self, axis: usize, min: F, max: F) -> Option<(u32, u32)> {
+        let count = if axis == 0 { self.columns } else { self.rows };
+        let size = self.tile_size[axis];
+        let end = self.origin[axis] + size * F::from_f64(count as f64);
+        if count == 0 || max < self.origin[axis] || min > end || min.is_nan() || max.is_nan() {
+            return None;
+        }
+        // 先限制在网格内再取整，避免负数向零取整
+        let index = |value: F| {
+            let value = value.clamp(self.origin[axis], end);
+            (((value - self.origin[axis]) / size).to_f64() as u32).min(count - 1)
+        };
+        Some((index(min), index(max)))
+    }
+}
+
+/// 裁剪到一个瓦片内的路径
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tile<F = f64> {
+    pub column: u32,
+    pub row: u32,
+    pub paths: Vec<Vec<Curve<F>>>,
+}
+
+/// 把曲线分配到网格的各个瓦片并裁剪，每个瓦片的结果与 `clip_to_rect` 相同
+///
+/// 每串曲线先按边界框覆盖的列裁剪成竖条，再把每个竖条按行裁剪，没有路径的瓦片不返回，结果按行、列排列
+pub fn clip_to_grid<F: Float>(
+    curves: &[Curve<F>],
+    grid: &TileGrid<F>,
+    closed: bool,
+) -> Vec<Tile<F>> {
+    let mut tiles: BTreeMap<(u32, u32), Vec<Vec<Curve<F>>>> = BTreeMap::new();
+    for chain in chains(curves, closed) {
+        let [min_x, min_y, max_x, max_y] = CollisionDetection::get_bounds(&chain).into_iter().fold(
+            [F::INFINITY, F::INFINITY, F::NEG_INFINITY, F::NEG_INFINITY],
+            |a, b| {
+                [
+                    a[0].min(b[0]),
+                    a[1].min(b[1]),
+                    a[2].max(b[2]),
+                    a[3].max(b[3]),
+                ]
+            },
+        );
+        let (Some(columns), Some(rows)) =
+            (grid.range(0, min_x, max_x), grid.range(1, min_y, max_y))
+        else {
+            continue;
+        };
+        let chain = [chain];
+        for column in columns.0..=columns.1 {
+            let [min_x, _, max_x, _] = grid.tile_rect(column, rows.0);
+            if !ordered(min_x, max_x) {
+                continue;
+            }
+            let strip = clip_axis(&chain, 0, min_x, max_x, closed);
+            if strip.is_empty() {
+                continue;
+            }
+            for row in rows.0..=rows.1 {
+                let [_, min_y, _, max_y] = grid.tile_rect(column, row);
+                if !ordered(min_y, max_y) {
+                    continue;
+                }
+                let paths = clip_axis(&strip, 1, min_y, max_y, closed);
+                if !paths.is_empty() {
+                    tiles.entry((row, column)).or_default().extend(paths);
+                }
+            }
+        }
+    }
+    tiles
+        .into_iter()
+        .map(|((row, column), paths)| Tile { column, row, paths })
+        .collect()
+}
@@ -1,108 +1,246 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{Bvh, Float};
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// 按序号压缩存储的碰撞结果，第 i 个边界框碰撞的序号为 `indices[offsets[i]..offsets[i + 1]]`，
+/// 每一段都按从小到大排列
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundsCollisions {
+    pub offsets: Vec<u32>,
+    pub indices: Vec<u32>,
+}
+
+impl BoundsCollisions {
+    /// 按第一组边界框的序号汇总碰撞对
+    fn from_pairs(len: usize, mut pairs: Vec<(u32, u32)>) -> Self {
+        pairs.sort_unstable();
+        pairs.dedup();
+        let mut offsets = vec![0; len + 1];
+        for &(a, _) in &pairs {
+            offsets[a as usize + 1] += 1;
+        }
+        for i in 0..len {
+            offsets[i + 1] += offsets[i];
+        }
+        let indices = pairs.into_iter().map(|(_, b)| b).collect();
+        BoundsCollisions { offsets, indices }
+    }
+
+    /// 第一组边界框的数量
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 与第一组中第 `i` 个边界框碰撞的序号
+    pub fn get(&self, i: usize) -> &[u32] {
+        &self.indices[self.offsets[i] as usize..self.offsets[i + 1] as usize]
+    }
+
+    /// 依次返回第一组中每个边界框碰撞的序号
+    pub fn iter(&self) -> impl Iterator<Item = &[u32]> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    /// 依次返回所有碰撞对 `(i, j)`
+    pub fn pairs(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.iter()
+            .enumerate()
+            .flat_map(|(i, items)| items.iter().map(move |&j| (i as u32, j)))
+    }
+}
+
+/// 矩形选择的方式
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RectSelection {
+    /// 边界框与矩形相交即选中
+    #[default]
+    Intersects,
+    /// 边界框完全在矩形内才选中
+    Contains,
+}
+
+/// 查找边界框碰撞的方式
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Broadphase {
+    /// 按一个坐标轴排序后扫描，自动选择边界框分布更稀疏的坐标轴
+    #[default]
+    SweepAndPrune,
+    /// 层次包围盒，适合在两个方向上都有聚集的分布，例如 L 形的布局
+    Bvh,
+}
+
 pub struct CollisionDetection;
-/// 检测两组曲线之间的边界碰撞
-/// 
-/// 通过比较边界框的最小和最大坐标值，对合并后的边界框按照 x 最小值进行排序
-/// 找出所有可能相交的曲线对
+/// 检测两组边界框之间的碰撞
+///
+/// 边界框的格式为 `[min_x, min_y, max_x, max_y]`，除了曲线也可以用于图形、图层或文字等任意对象。
+/// 默认把合并后的边界框按照一个坐标轴上的最小值排序后扫描，找出所有可能相交的对象对
 impl CollisionDetection {
-    pub fn get_bounds(curves: &[[f64; 8]]) -> Vec<[f64; 4]> {
-        curves
-            .iter()
-            .map(|&v| {
-                let (mut min_x, mut min_y, mut max_x, mut max_y) = (
-                    f64::INFINITY,
-                    f64::INFINITY,
-                    f64::NEG_INFINITY,
-                    f64::NEG_INFINITY,
-                );
-                for (i, &val) in v.iter().enumerate() {
-                    if i % 2 == 0 {
-                        min_x = min_x.min(val);
-                        max_x = max_x.max(val);
-                    } else {
-                        min_y = min_y.min(val);
-                        max_y = max_y.max(val);
-                    }
-                }
-                [min_x, min_y, max_x, max_y]
-            })
-            .collect()
+    /// 控制点的边界框 `[min_x, min_y, max_x, max_y]`，包含整条曲线
+    pub fn get_curve_bounds<F: Float>(v: &[F; 8]) -> [F; 4] {
+        let (mut min_x, mut min_y, mut max_x, mut max_y) =
+            (F::INFINITY, F::INFINITY, F::NEG_INFINITY, F::NEG_INFINITY);
+        for (i, &val) in v.iter().enumerate() {
+            if i % 2 == 0 {
+                min_x = min_x.min(val);
+                max_x = max_x.max(val);
+            } else {
+                min_y = min_y.min(val);
+                max_y = max_y.max(val);
+            }
+        }
+        [min_x, min_y, max_x, max_y]
+    }
+
+    /// 每条曲线的边界框
+    pub fn get_bounds<F: Float>(curves: &[[F; 8]]) -> Vec<[F; 4]> {
+        curves.iter().map(Self::get_curve_bounds).collect()
     }
-    pub fn find_curve_bounds_collisions(
-        curves1: &Vec<[f64; 8]>,
-        curves2: &Vec<[f64; 8]>,
+
+    /// 两组曲线之间边界框的碰撞，`is_self` 时只使用 `curves1`
+    pub fn find_curve_bounds_collisions<F: Float>(
+        curves1: &[[F; 8]],
+        curves2: &[[F; 8]],
         is_self: bool,
-        tolerance: f64,
-    ) -> Vec<Vec<i32>> {
+        tolerance: F,
+        broadphase: Broadphase,
+    ) -> BoundsCollisions {
         let bounds1 = Self::get_bounds(curves1);
         if is_self {
-            Self::find_bounds_collisions(&bounds1, &bounds1, is_self, tolerance)
+            Self::find_bounds_collisions(&bounds1, &bounds1, is_self, tolerance, broadphase)
         } else {
             let bounds2 = Self::get_bounds(curves2);
-            Self::find_bounds_collisions(&bounds1, &bounds2, is_self, tolerance)
+            Self::find_bounds_collisions(&bounds1, &bounds2, is_self, tolerance, broadphase)
         }
     }
 
-    pub fn binary_search(
-        indices: &Vec<usize>,
-        bounds: &Vec<[f64; 4]>,
+    /// 在按 `bounds[i][coord]` 升序排列的 `indices` 中查找最后一个不超过 `value` 的位置
+    pub fn binary_search<F: Float>(
+        indices: &[usize],
+        bounds: &[[F; 4]],
         coord: usize,
-        value: f64,
+        value: F,
     ) -> Option<usize> {
         indices
             .binary_search_by(|&i| {
                 bounds[i][coord]
                     .partial_cmp(&value)
-                    .unwrap_or(std::cmp::Ordering::Less)
+                    .unwrap_or(Ordering::Less)
             })
             .map_or_else(|err| if err > 0 { Some(err - 1) } else { None }, Some)
     }
 
-    pub fn find_bounds_collisions(
-        bounds_a: &Vec<[f64; 4]>,
-        bounds_b: &Vec<[f64; 4]>,
+    /// 两组边界框之间的碰撞，间隙不超过 `tolerance` 的也算碰撞
+    ///
+    /// 结果按 `bounds_a` 的序号排列，记录碰撞的 `bounds_b` 序号。`is_self` 时只使用 `bounds_a`，
+    /// 碰撞对在两个方向上都会记录。`is_self` 或两组边界框相同时，每个边界框都与自身碰撞，与容差无关。
+    /// 坐标含 NaN 的边界框不与任何边界框碰撞。结果与 `broadphase` 无关
+    pub fn find_bounds_collisions<F: Float>(
+        bounds_a: &[[F; 4]],
+        bounds_b: &[[F; 4]],
         is_self: bool,
-        tolerance: f64,
-    ) -> Vec<Vec<i32>> {
+        tolerance: F,
+        broadphase: Broadphase,
+    ) -> BoundsCollisions {
+        let mut pairs = match broadphase {
+            Broadphase::SweepAndPrune => Self::sweep(bounds_a, bounds_b, is_self, tolerance),
+            Broadphase::Bvh => {
+                let bvh = Bvh::new(if is_self { bounds_a } else { bounds_b });
+                let mut pairs = Vec::new();
+                for (i, b) in bounds_a.iter().enumerate() {
+                    bvh.query(*b, tolerance, |j| pairs.push((i as u32, j)));
+                }
+                pairs
+            }
+        };
+        if is_self || bounds_a == bounds_b {
+            for (i, b) in bounds_a.iter().enumerate() {
+                if !b.iter().any(|value| value.is_nan()) {
+                    pairs.push((i as u32, i as u32));
+                }
+            }
+        }
+        BoundsCollisions::from_pairs(bounds_a.len(), pairs)
+    }
+
+    /// 扫描的坐标轴，边界框在该轴上的长度之和与分布范围之比更小，同时处于扫描中的边界框更少
+    fn sweep_axis<'a, F: Float + 'a>(bounds: impl Iterator<Item = &'a [F; 4]>) -> usize {
+        let mut extent = [F::ZERO; 2];
+        let (mut min, mut max) = ([F::INFINITY; 2], [F::NEG_INFINITY; 2]);
+        for b in bounds {
+            for axis in 0..2 {
+                extent[axis] += b[axis + 2] - b[axis];
+                min[axis] = min[axis].min(b[axis]);
+                max[axis] = max[axis].max(b[axis + 2]);
+            }
+        }
+        if extent[1] * (max[0] - min[0]) < extent[0] * (max[1] - min[1]) {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn sweep<F: Float>(
+        bounds_a: &[[F; 4]],
+        bounds_b: &[[F; 4]],
+        is_self: bool,
+        tolerance: F,
+    ) -> Vec<(u32, u32)> {
         let all_bounds = if is_self {
-            bounds_a.clone()
+            bounds_a.to_vec()
         } else {
-            [bounds_a.as_slice(), bounds_b.as_slice()].concat()
+            [bounds_a, bounds_b].concat()
         };
         let length_a = bounds_a.len();
-        let mut all_indices_by_pri0: Vec<usize> = (0..all_bounds.len()).collect();
+        // 合并后第二组的序号从 `offset_b` 开始
+        let offset_b = if is_self { 0 } else { length_a };
+        let mut all_indices_by_pri0: Vec<usize> = (0..all_bounds.len())
+            .filter(|&i| !all_bounds[i].iter().any(|value| value.is_nan()))
+            .collect();
+        // 主轴上的最小值、最大值和副轴上的最小值、最大值在边界框中的位置
+        let pri0 = Self::sweep_axis(all_indices_by_pri0.iter().map(|&i| &all_bounds[i]));
+        let (pri1, sec0, sec1) = (pri0 + 2, 1 - pri0, 3 - pri0);
 
         all_indices_by_pri0.sort_unstable_by(|&i1, &i2| {
-            all_bounds[i1][0].partial_cmp(&all_bounds[i2][0]).unwrap()
+            all_bounds[i1][pri0]
+                .partial_cmp(&all_bounds[i2][pri0])
+                .unwrap_or(Ordering::Equal)
         });
 
         let mut active_indices_by_pri1: Vec<usize> = Vec::new();
-        let mut all_collisions: Vec<Vec<i32>> = vec![Vec::new(); length_a];
+        let mut pairs: Vec<(u32, u32)> = Vec::new();
 
         for &cur_index in all_indices_by_pri0.iter() {
             let cur_bounds = &all_bounds[cur_index];
-            let orig_index = if is_self {
-                cur_index as i32
-            } else {
-                cur_index as i32 - length_a as i32
-            };
             let is_current_a = cur_index < length_a;
             let is_current_b = is_self || !is_current_a;
-            let mut cur_collisions: Vec<i32> = if is_current_a { Vec::new() } else { Vec::new() };
 
             if !active_indices_by_pri1.is_empty() {
                 let prune_count: usize = match Self::binary_search(
                     &active_indices_by_pri1,
                     &all_bounds,
-                    2,
-                    cur_bounds[0] - tolerance,
+                    pri1,
+                    cur_bounds[pri0] - tolerance,
                 ) {
                     Some(count) => count + 1,
                     None => 0,
                 };
                 active_indices_by_pri1.drain(..prune_count);
 
-                let cur_sec1 = cur_bounds[3];
-                let cur_sec0 = cur_bounds[1];
+                let cur_sec1 = cur_bounds[sec1];
+                let cur_sec0 = cur_bounds[sec0];
 
                 for &active_index in active_indices_by_pri1.iter() {
                     let active_bounds = &all_bounds[active_index];
@@ -110,45 +248,68 @@ impl CollisionDetection {
                     let is_active_b = is_self || active_index >= length_a;
 
                     if ((is_current_a && is_active_b) || (is_current_b && is_active_a))
-                        && (cur_sec1 >= active_bounds[1] - tolerance
-                            && cur_sec0 <= active_bounds[3] + tolerance)
+                        && (cur_sec1 >= active_bounds[sec0] - tolerance
+                            && cur_sec0 <= active_bounds[sec1] + tolerance)
                     {
                         if is_current_a && is_active_b {
-                            cur_collisions.push(if is_self {
-                                active_index as i32
-                            } else {
-                                (active_index - length_a) as i32
-                            });
+                            pairs.push((cur_index as u32, (active_index - offset_b) as u32));
                         }
                         if is_current_b && is_active_a {
-                            all_collisions[active_index].push(orig_index);
+                            pairs.push((active_index as u32, (cur_index - offset_b) as u32));
                         }
                     }
                 }
             }
 
-            if is_current_a {
-                if bounds_a == bounds_b {
-                    cur_collisions.push(cur_index as i32);
-                }
-                all_collisions[cur_index] = cur_collisions;
-            }
-
-            let cur_pri1 = cur_bounds[2];
-            let index = match Self::binary_search(&active_indices_by_pri1, &all_bounds, 2, cur_pri1)
-            {
-                Some(count) => count + 1,
-                None => 0,
-            };
+            let cur_pri1 = cur_bounds[pri1];
+            let index =
+                match Self::binary_search(&active_indices_by_pri1, &all_bounds, pri1, cur_pri1) {
+                    Some(count) => count + 1,
+                    None => 0,
+                };
             active_indices_by_pri1.insert(index, cur_index);
         }
 
-        for collisions in all_collisions.iter_mut() {
-            if !collisions.is_empty() {
-                collisions.sort_unstable();
-            }
-        }
+        pairs
+    }
+
+    /// 包含 `point` 的边界框序号，间隙不超过 `tolerance` 的也算包含，可用于点选
+    pub fn find_bounds_at_point<F: Float>(
+        bounds: &[[F; 4]],
+        point: [F; 2],
+        tolerance: F,
+    ) -> Vec<u32> {
+        Self::find_bounds_in_rect(
+            bounds,
+            [
+                point[0] - tolerance,
+                point[1] - tolerance,
+                point[0] + tolerance,
+                point[1] + tolerance,
+            ],
+            RectSelection::Intersects,
+        )
+    }
 
-        all_collisions
+    /// 被矩形 `rect` 选中的边界框序号，可用于框选，格式与边界框相同
+    pub fn find_bounds_in_rect<F: Float>(
+        bounds: &[[F; 4]],
+        rect: [F; 4],
+        selection: RectSelection,
+    ) -> Vec<u32> {
+        let [min_x, min_y, max_x, max_y] = rect;
+        bounds
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| match selection {
+                RectSelection::Intersects => {
+                    b[0] <= max_x && b[2] >= min_x && b[1] <= max_y && b[3] >= min_y
+                }
+                RectSelection::Contains => {
+                    b[0] >= min_x && b[2] <= max_x && b[1] >= min_y && b[3] <= max_y
+                }
+            })
+            .map(|(i, _)| i as u32)
+            .collect()
     }
 }
@@ -0,0 +1,118 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm-serde")]
+use tsify::Tsify;
+
+use crate::location::unit_tangent;
+use crate::{Curve, Float};
+
+/// 第二条曲线在相交点处穿过第一条曲线的方向
+///
+/// 左右以第一条曲线的前进方向为准，y 轴向下（SVG 坐标系）时与屏幕上看到的一致
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "wasm-serde", derive(Tsify))]
+pub enum Crossing {
+    /// 从左侧穿到右侧，切线叉积 `tangent1 × tangent2` 为正
+    LeftToRight,
+    /// 从右侧穿到左侧，切线叉积为负
+    RightToLeft,
+    /// 相切后留在同一侧，或者两条曲线重合，没有穿过
+    #[default]
+    Touch,
+}
+
+impl Crossing {
+    /// 从左到右为 1，从右到左为 -1，没有穿过为 0
+    pub fn sign(self) -> i8 {
+        match self {
+            Crossing::LeftToRight => 1,
+            Crossing::RightToLeft => -1,
+            Crossing::Touch => 0,
+        }
+    }
+
+    /// 交换两条曲线后的方向
+    pub fn reversed(self) -> Self {
+        match self {
+            Crossing::LeftToRight => Crossing::RightToLeft,
+            Crossing::RightToLeft => Crossing::LeftToRight,
+            Crossing::Touch => Crossing::Touch,
+        }
+    }
+}
+
+/// 曲线在 `t` 处的一阶、二阶和三阶导数
+fn derivatives<F: Float>(v: &Curve<F>, t: F) -> [[F; 2]; 3] {
+    let (two, three, six) = (F::from_f64(2.0), F::from_f64(3.0), F::from_f64(6.0));
+    let mut result = [[F::ZERO; 2]; 3];
+    for k in 0..2 {
+        let (p0, p1, p2, p3) = (v[k], v[k + 2], v[k + 4], v[k + 6]);
+        let c = three * (p1 - p0);
+        let b = three * (p2 - p1) - c;
+        let a = p3 - p0 - c - b;
+        result[0][k] = (three * a * t + two * b) * t + c;
+        result[1][k] = six * a * t + two * b;
+        result[2][k] = six * a;
+    }
+    result
+}
+
+/// 以 `tangent` 为 x 轴的局部坐标系中，把曲线写成 y = f(x) 后在 `t` 处的 `(dx/dt, f'', f''')`
+fn graph_derivatives<F: Float>(v: &Curve<F>, t: F, tangent: [F; 2]) -> Option<(F, F, F)> {
+    let local = |d: [F; 2]| {
+        (
+            tangent[0] * d[0] + tangent[1] * d[1],
+            tangent[0] * d[1] - tangent[1] * d[0],
+        )
+    };
+    let [d1, d2, d3] = derivatives(v, t);
+    let ((x1, y1), (x2, y2), (x3, y3)) = (local(d1), local(d2), local(d3));
+    if x1 == F::ZERO {
+        return None;
+    }
+    let numerator = y2 * x1 - y1 * x2;
+    let f2 = numerator / (x1 * x1 * x1);
+    let f3 =
+        ((y3 * x1 - y1 * x3) * x1 - F::from_f64(3.0) * x2 * numerator) / (x1 * x1 * x1 * x1 * x1);
+    Some((x1, f2, f3))
+}
+
+/// 第二条曲线在 `v2` 的 `t2` 处穿过 `v1` 的 `t1` 处的方向
+///
+/// 一般由两条切线的叉积决定。相切时把两条曲线写成公切线上的函数比较：
+/// 二阶导数不同说明第二条曲线留在同一侧，相同则由三阶导数决定穿过的方向
+pub fn get_crossing<F: Float>(v1: &Curve<F>, t1: F, v2: &Curve<F>, t2: F) -> Crossing {
+    let tangent1 = unit_tangent(v1, t1);
+    let tangent2 = unit_tangent(v2, t2);
+    let cross = tangent1[0] * tangent2[1] - tangent1[1] * tangent2[0];
+    if cross > F::GEOMETRIC_EPSILON {
+        return Crossing::LeftToRight;
+    }
+    if cross < -F::GEOMETRIC_EPSILON {
+        return Crossing::RightToLeft;
+    }
+
+    let (Some((s1, f2_1, f3_1)), Some((s2, f2_2, f3_2))) = (
+        graph_derivatives(v1, t1, tangent1),
+        graph_derivatives(v2, t2, tangent1),
+    ) else {
+        return Crossing::Touch;
+    };
+    let (d2, d3) = (f2_2 - f2_1, f3_2 - f3_1);
+    // 参数误差带来的二阶导数之差与三阶导数乘以位置误差相当
+    let tolerance = d3.abs() * s1.abs().max(s2.abs()) * F::CURVETIME_EPSILON.sqrt();
+    if d3 == F::ZERO || d2.abs() > tolerance {
+        return Crossing::Touch;
+    }
+    // 第二条曲线沿公切线反向前进时，经过的两侧顺序相反
+    if (d3 > F::ZERO) == (s2 > F::ZERO) {
+        Crossing::LeftToRight
+    } else {
+        Crossing::RightToLeft
+    }
+}
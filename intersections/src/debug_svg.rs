@@ -0,0 +1,157 @@
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::collision_detection::{Broadphase, CollisionDetection};
+use crate::{evaluate, Curve, Float};
+
+/// 第 k 个候选曲线对的颜色，按黄金角分布色相
+fn pair_color(k: usize) -> String {
+    format!("hsl({:.0}, 75%, 45%)", (k as f64 * 137.508) % 360.0)
+}
+
+fn bounds<F: Float>(curves: &[Curve<F>]) -> Vec<[f64; 4]> {
+    CollisionDetection::get_bounds(curves)
+        .into_iter()
+        .map(|b| b.map(|v| v.to_f64()))
+        .collect()
+}
+
+fn path_data<F: Float>(v: &Curve<F>) -> String {
+    let [p1x, p1y, c1x, c1y, c2x, c2y, p2x, p2y] = v.map(|v| v.to_f64());
+    format!("M {p1x} {p1y} C {c1x} {c1y} {c2x} {c2y} {p2x} {p2y}")
+}
+
+/// 输出调试用的 SVG，包含曲线、边界框、候选曲线对和相交点
+///
+/// `curves1` 为蓝色，`curves2` 为橙色，`is_self` 时只绘制 `curves1`。
+/// 候选曲线对以彩色线段连接两个边界框的中心，没有报告相交点的曲线对为虚线。
+/// 相交点标注其在 `locations` 中的序号，悬停可查看参数
+pub fn render_debug_svg<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    is_self: bool,
+    locations: &[[F; 8]],
+) -> String {
+    let curves2 = if is_self { curves1 } else { curves2 };
+    let (bounds1, bounds2) = (bounds(curves1), bounds(curves2));
+    let collisions = CollisionDetection::find_curve_bounds_collisions(
+        curves1,
+        curves2,
+        is_self,
+        F::GEOMETRIC_EPSILON,
+        Broadphase::default(),
+    );
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (
+        f64::INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NEG_INFINITY,
+    );
+    for b in bounds1.iter().chain(&bounds2) {
+        min_x = min_x.min(b[0]);
+        min_y = min_y.min(b[1]);
+        max_x = max_x.max(b[2]);
+        max_y = max_y.max(b[3]);
+    }
+    if min_x > max_x {
+        (min_x, min_y, max_x, max_y) = (0.0, 0.0, 1.0, 1.0);
+    }
+    let size = (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+    let padding = size * 0.05;
+    let font_size = size / 60.0;
+    let radius = size / 200.0;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"800\">\n",
+        min_x - padding,
+        min_y - padding,
+        max_x - min_x + padding * 2.0,
+        max_y - min_y + padding * 2.0,
+    );
+    svg += &format!(
+        "<style>path, rect, line, circle {{ vector-effect: non-scaling-stroke; }} \
+         text {{ font: {font_size}px sans-serif; paint-order: stroke; stroke: white; stroke-width: {}; }}</style>\n",
+        font_size / 4.0
+    );
+
+    svg += "<g id=\"bounds\" fill=\"none\" stroke=\"#999\" stroke-dasharray=\"2 2\">\n";
+    for (bounds, group) in [(&bounds1, 1), (&bounds2, 2)] {
+        if is_self && group == 2 {
+            continue;
+        }
+        for (i, b) in bounds.iter().enumerate() {
+            svg += &format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"><title>curves{group}[{i}]</title></rect>\n",
+                b[0],
+                b[1],
+                b[2] - b[0],
+                b[3] - b[1]
+            );
+        }
+    }
+    svg += "</g>\n";
+
+    let found: BTreeSet<(usize, usize)> = locations
+        .iter()
+        .map(|l| (l[1].to_f64() as usize, l[5].to_f64() as usize))
+        .collect();
+    svg += "<g id=\"candidates\" stroke-width=\"1.5\">\n";
+    let mut k = 0;
+    for (i, items) in collisions.iter().enumerate() {
+        for &j in items {
+            let j = j as usize;
+            if is_self && j <= i {
+                continue;
+            }
+            let (b1, b2) = (bounds1[i], bounds2[j]);
+            svg += &format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\"{}><title>{i} × {j}</title></line>\n",
+                (b1[0] + b1[2]) / 2.0,
+                (b1[1] + b1[3]) / 2.0,
+                (b2[0] + b2[2]) / 2.0,
+                (b2[1] + b2[3]) / 2.0,
+                pair_color(k),
+                if found.contains(&(i, j)) { "" } else { " stroke-dasharray=\"4 2\"" }
+            );
+            k += 1;
+        }
+    }
+    svg += "</g>\n";
+
+    svg += "<g id=\"curves\" fill=\"none\" stroke-width=\"2\">\n";
+    for (curves, group, color) in [(curves1, 1, "#1f77b4"), (curves2, 2, "#ff7f0e")] {
+        if is_self && group == 2 {
+            continue;
+        }
+        for (i, v) in curves.iter().enumerate() {
+            svg += &format!(
+                "<path d=\"{}\" stroke=\"{color}\"><title>curves{group}[{i}]</title></path>\n",
+                path_data(v)
+            );
+            if let Some([x, y]) = evaluate(v, F::from_f64(0.5), 0) {
+                svg += &format!(
+                    "<text x=\"{}\" y=\"{}\" fill=\"{color}\">{i}</text>\n",
+                    x.to_f64(),
+                    y.to_f64()
+                );
+            }
+        }
+    }
+    svg += "</g>\n";
+
+    svg += "<g id=\"locations\" fill=\"#d62728\">\n";
+    for (k, l) in locations.iter().enumerate() {
+        let [t1, i1, x1, y1, t2, i2, ..] = l.map(|v| v.to_f64());
+        svg += &format!(
+            "<circle cx=\"{x1}\" cy=\"{y1}\" r=\"{radius}\"><title>#{k}: curve {i1} t={t1}, curve {i2} t={t2}</title></circle>\n\
+             <text x=\"{}\" y=\"{}\">{k}</text>\n",
+            x1 + radius,
+            y1 - radius
+        );
+    }
+    svg += "</g>\n</svg>\n";
+    svg
+}
@@ -0,0 +1,369 @@
+use alloc::collections::BinaryHeap;
+use core::cmp::Ordering;
+
+use crate::collision_detection::{Broadphase, CollisionDetection};
+use crate::{evaluate, get_fatline, signed_distance, split_cubic_bezier, Curve, Float};
+
+/// 一次查询最多处理的片段数量
+pub(crate) const MAX_STEPS: u32 = 1 << 16;
+
+/// 两条曲线之间距离最近的一对点，`curve1`、`curve2` 为所在曲线的序号
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClosestPoints<F = f64> {
+    pub distance: F,
+    pub curve1: u32,
+    pub t1: F,
+    pub point1: [F; 2],
+    pub curve2: u32,
+    pub t2: F,
+    pub point2: [F; 2],
+    /// 搜索处理的片段数量达到上限后提前结束，`distance` 只是上界
+    pub truncated: bool,
+}
+
+/// 阈值查询处理的片段数量达到上限后提前结束，无法确定是否存在足够近的点
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchTruncated;
+
+impl<F: Float> ClosestPoints<F> {
+    /// 按 `[t1, i1, x1, y1, t2, i2, x2, y2, distance]` 展开，前 8 项与相交点的格式相同
+    pub fn to_array(&self) -> [F; 9] {
+        [
+            self.t1,
+            F::from_f64(self.curve1 as f64),
+            self.point1[0],
+            self.point1[1],
+            self.t2,
+            F::from_f64(self.curve2 as f64),
+            self.point2[0],
+            self.point2[1],
+            self.distance,
+        ]
+    }
+}
+
+/// 曲线上参数区间 `[t_min, t_max]` 的片段
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Segment<F> {
+    pub(crate) curve: u32,
+    pub(crate) v: Curve<F>,
+    pub(crate) t_min: F,
+    pub(crate) t_max: F,
+}
+
+impl<F: Float> Segment<F> {
+    pub(crate) fn new(curve: usize, v: &Curve<F>) -> Self {
+        Segment {
+            curve: curve as u32,
+            v: *v,
+            t_min: F::ZERO,
+            t_max: F::ONE,
+        }
+    }
+
+    pub(crate) fn split(&self) -> (Self, Self) {
+        let (left, right) = split_cubic_bezier(&self.v, F::from_f64(0.5));
+        let t_mid = (self.t_min + self.t_max) / F::from_f64(2.0);
+        (
+            Segment {
+                v: left,
+                t_max: t_mid,
+                ..*self
+            },
+            Segment {
+                v: right,
+                t_min: t_mid,
+                ..*self
+            },
+        )
+    }
+
+    /// 起点、中点和终点
+    pub(crate) fn samples(&self) -> [(F, [F; 2]); 3] {
+        let (left, _) = split_cubic_bezier(&self.v, F::from_f64(0.5));
+        let v = &self.v;
+        [
+            (self.t_min, [v[0], v[1]]),
+            (
+                (self.t_min + self.t_max) / F::from_f64(2.0),
+                [left[6], left[7]],
+            ),
+            (self.t_max, [v[6], v[7]]),
+        ]
+    }
+
+    /// 参数区间还能继续对半分割
+    pub(crate) fn splittable(&self) -> bool {
+        self.t_max - self.t_min > F::CURVETIME_EPSILON
+    }
+
+    fn size(&self) -> F {
+        let [min_x, min_y, max_x, max_y] = CollisionDetection::get_curve_bounds(&self.v);
+        (max_x - min_x).max(max_y - min_y)
+    }
+}
+
+/// 按下界从小到大出堆的片段对
+struct Pair<F> {
+    bound: F,
+    a: Segment<F>,
+    b: Segment<F>,
+}
+
+impl<F: Float> PartialEq for Pair<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Float> Eq for Pair<F> {}
+
+impl<F: Float> PartialOrd for Pair<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for Pair<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .bound
+            .partial_cmp(&self.bound)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// `v2` 的控制点位于 `v1` 胖线之外的距离，胖线包含 `v1`，控制点的凸包包含 `v2`
+fn fatline_gap<F: Float>(v1: &Curve<F>, v2: &Curve<F>) -> F {
+    let (d_min, d_max, _, _) = get_fatline(v1);
+    let (mut min, mut max) = (F::INFINITY, F::NEG_INFINITY);
+    for k in 0..4 {
+        let d = signed_distance(v1[0], v1[1], v1[6], v1[7], v2[k * 2], v2[k * 2 + 1], false);
+        min = min.min(d);
+        max = max.max(d);
+    }
+    (min - d_max).max(d_min - max).max(F::ZERO)
+}
+
+/// 两个片段之间距离的下界，取边界框间隙与两个方向胖线间隙中的最大值
+fn lower_bound<F: Float>(a: &Segment<F>, b: &Segment<F>) -> F {
+    let ba = CollisionDetection::get_curve_bounds(&a.v);
+    let bb = CollisionDetection::get_curve_bounds(&b.v);
+    let dx = (ba[0] - bb[2]).max(bb[0] - ba[2]).max(F::ZERO);
+    let dy = (ba[1] - bb[3]).max(bb[1] - ba[3]).max(F::ZERO);
+    (dx * dx + dy * dy)
+        .sqrt()
+        .max(fatline_gap(&a.v, &b.v))
+        .max(fatline_gap(&b.v, &a.v))
+}
+
+/// 分支定界搜索最近点
+///
+/// 每次取出下界最小的片段对，用端点和中点的距离更新上界，再把较大的片段对半分割。
+/// 给定 `threshold` 时只保留下界不超过阈值的片段对，找到距离不超过阈值的点后立即返回，
+/// 没有找到时只在提前结束的情况下返回当前结果。处理的片段数量超过 `MAX_STEPS` 时提前结束并标记 `truncated`
+fn search<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    pairs: impl Iterator<Item = (usize, usize)>,
+    threshold: Option<F>,
+) -> Option<ClosestPoints<F>> {
+    let limit = threshold.unwrap_or(F::INFINITY);
+    let mut heap = BinaryHeap::new();
+    for (i, j) in pairs {
+        let (a, b) = (Segment::new(i, &curves1[i]), Segment::new(j, &curves2[j]));
+        let bound = lower_bound(&a, &b);
+        // 坐标为 NaN 时下界也为 NaN，仍然保留以便返回结果
+        if bound <= limit || bound.is_nan() {
+            heap.push(Pair { bound, a, b });
+        }
+    }
+
+    let mut best: Option<ClosestPoints<F>> = None;
+    let mut steps = 0;
+    let mut truncated = false;
+    while let Some(Pair { bound, a, b }) = heap.pop() {
+        if let Some(best) = &best {
+            if bound >= best.distance - F::GEOMETRIC_EPSILON {
+                break;
+            }
+        }
+        steps += 1;
+        if steps > MAX_STEPS {
+            truncated = true;
+            break;
+        }
+
+        for (t1, p1) in a.samples() {
+            for (t2, p2) in b.samples() {
+                let (dx, dy) = (p1[0] - p2[0], p1[1] - p2[1]);
+                let distance = (dx * dx + dy * dy).sqrt();
+                if best.is_none_or(|best| distance < best.distance) {
+                    best = Some(ClosestPoints {
+                        distance,
+                        curve1: a.curve,
+                        t1,
+                        point1: p1,
+                        curve2: b.curve,
+                        t2,
+                        point2: p2,
+                        truncated: false,
+                    });
+                }
+            }
+        }
+        if let (Some(best), Some(threshold)) = (best, threshold) {
+            if best.distance <= threshold {
+                return Some(best);
+            }
+        }
+
+        let split_a = match (a.splittable(), b.splittable()) {
+            (false, false) => continue,
+            (true, false) => true,
+            (false, true) => false,
+            (true, true) => a.size() >= b.size(),
+        };
+        let upper = best.map_or(F::INFINITY, |best| best.distance);
+        let mut push = |a: Segment<F>, b: Segment<F>| {
+            let bound = lower_bound(&a, &b);
+            if bound < upper - F::GEOMETRIC_EPSILON && bound <= limit {
+                heap.push(Pair { bound, a, b });
+            }
+        };
+        if split_a {
+            let (left, right) = a.split();
+            push(left, b);
+            push(right, b);
+        } else {
+            let (left, right) = b.split();
+            push(a, left);
+            push(a, right);
+        }
+    }
+    // 阈值查询没有找到足够近的点
+    if threshold.is_some() && !truncated {
+        return None;
+    }
+    best.map(|best| ClosestPoints {
+        truncated,
+        ..polish(
+            &curves1[best.curve1 as usize],
+            &curves2[best.curve2 as usize],
+            best,
+        )
+    })
+}
+
+/// 用高斯-牛顿迭代修正最近点，分割受参数精度限制，相交时的距离可以进一步收敛到零
+fn polish<F: Float>(v1: &Curve<F>, v2: &Curve<F>, mut best: ClosestPoints<F>) -> ClosestPoints<F> {
+    for _ in 0..8 {
+        let (Some(d1), Some(d2)) = (evaluate(v1, best.t1, 1), evaluate(v2, best.t2, 1)) else {
+            break;
+        };
+        let (fx, fy) = (
+            best.point1[0] - best.point2[0],
+            best.point1[1] - best.point2[1],
+        );
+        // J = [d1, -d2]，解 JᵀJ δ = -Jᵀf
+        let (a, b, c) = (
+            d1[0] * d1[0] + d1[1] * d1[1],
+            -(d1[0] * d2[0] + d1[1] * d2[1]),
+            d2[0] * d2[0] + d2[1] * d2[1],
+        );
+        let (g1, g2) = (d1[0] * fx + d1[1] * fy, -(d2[0] * fx + d2[1] * fy));
+        let det = a * c - b * b;
+        if det.abs() <= F::MACHINE_EPSILON * a * c {
+            break;
+        }
+        let t1 = (best.t1 - (c * g1 - b * g2) / det).clamp(F::ZERO, F::ONE);
+        let t2 = (best.t2 - (a * g2 - b * g1) / det).clamp(F::ZERO, F::ONE);
+        let (Some(p1), Some(p2)) = (evaluate(v1, t1, 0), evaluate(v2, t2, 0)) else {
+            break;
+        };
+        let (dx, dy) = (p1[0] - p2[0], p1[1] - p2[1]);
+        let distance = (dx * dx + dy * dy).sqrt();
+        if distance.is_nan() || distance >= best.distance {
+            break;
+        }
+        best = ClosestPoints {
+            distance,
+            t1,
+            point1: p1,
+            t2,
+            point2: p2,
+            ..best
+        };
+    }
+    best
+}
+
+/// 两条曲线之间的最短距离及对应的最近点，误差约为 `F::GEOMETRIC_EPSILON`，提前结束时标记 `truncated`
+pub fn min_distance<F: Float>(v1: &Curve<F>, v2: &Curve<F>) -> ClosestPoints<F> {
+    search(&[*v1], &[*v2], core::iter::once((0, 0)), None).unwrap()
+}
+
+/// 两条路径之间的最短距离及对应的最近点，任一路径为空时返回 `None`，提前结束时标记 `truncated`
+///
+/// 逐步扩大容差做边界框碰撞检测，直到有曲线对碰撞，搜索这些曲线对得到上界后，
+/// 再搜索边界框间隙不超过上界的曲线对
+pub fn min_path_distance<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+) -> Option<ClosestPoints<F>> {
+    if curves1.is_empty() || curves2.is_empty() {
+        return None;
+    }
+    let search_within = |tolerance: F| {
+        let collisions = CollisionDetection::find_curve_bounds_collisions(
+            curves1,
+            curves2,
+            false,
+            tolerance,
+            Broadphase::default(),
+        );
+        let pairs = collisions.pairs().map(|(i, j)| (i as usize, j as usize));
+        search(curves1, curves2, pairs, None)
+    };
+    let mut tolerance = F::GEOMETRIC_EPSILON;
+    while tolerance < F::INFINITY {
+        if let Some(best) = search_within(tolerance) {
+            // 间隙超过容差的曲线对可能更近
+            if best.distance > tolerance {
+                return search_within(best.distance);
+            }
+            return Some(best);
+        }
+        tolerance = tolerance * F::from_f64(16.0);
+    }
+    // 坐标不是有限值时边界框不会碰撞，搜索全部曲线对
+    let pairs = (0..curves1.len()).flat_map(|i| (0..curves2.len()).map(move |j| (i, j)));
+    search(curves1, curves2, pairs, None)
+}
+
+/// 查找两条路径之间距离不超过 `threshold` 的一对点，找到后立即返回，不保证是最近的一对
+///
+/// 先用扩大了 `threshold` 的边界框筛选曲线对，没有这样的点时返回 `Ok(None)`。
+/// 搜索提前结束且没有找到时返回 `Err(SearchTruncated)`，此时不能断定没有足够近的点
+pub fn find_within_distance<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    threshold: F,
+) -> Result<Option<ClosestPoints<F>>, SearchTruncated> {
+    let collisions = CollisionDetection::find_curve_bounds_collisions(
+        curves1,
+        curves2,
+        false,
+        threshold,
+        Broadphase::default(),
+    );
+    let pairs = collisions.pairs().map(|(i, j)| (i as usize, j as usize));
+    match search(curves1, curves2, pairs, Some(threshold)) {
+        Some(best) if best.distance <= threshold => Ok(Some(ClosestPoints {
+            truncated: false,
+            ..best
+        })),
+        Some(_) => Err(SearchTruncated),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,249 @@
+//! C 接口，曲线以 `[p1x, p1y, c1x, c1y, c2x, c2y, p2x, p2y]` 连续存放，`len` 为曲线数量
+//!
+//! 由本库分配的结果需要调用对应的 `*_free` 函数释放。返回 `int32_t` 的函数成功时返回 `INTERSECTIONS_OK`
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::{ptr, slice};
+
+use crate::collision_detection::{Broadphase, CollisionDetection};
+use crate::{
+    evaluate, get_intersections_with_policy, split_cubic_bezier_part, Curve, EndpointPolicy,
+};
+
+/// 保留所有端点接触
+pub const INTERSECTIONS_INCLUDE_ALL: u32 = 0;
+/// 排除同时位于两条曲线端点上的接触
+pub const INTERSECTIONS_EXCLUDE_SHARED: u32 = 1;
+/// 排除位于任一曲线端点上的接触
+pub const INTERSECTIONS_EXCLUDE_ALL: u32 = 2;
+
+/// 成功
+pub const INTERSECTIONS_OK: i32 = 0;
+/// `policy` 不是 `INTERSECTIONS_INCLUDE_ALL`、`INTERSECTIONS_EXCLUDE_SHARED` 或 `INTERSECTIONS_EXCLUDE_ALL`
+pub const INTERSECTIONS_ERROR_INVALID_POLICY: i32 = 1;
+
+/// 相交点结果，`data` 中每 8 个数为一个相交点 `[t1, i1, x1, y1, t2, i2, x2, y2]`，`len` 为相交点数量
+#[repr(C)]
+pub struct IntersectionsLocations {
+    pub data: *mut f64,
+    pub len: usize,
+}
+
+/// 边界碰撞结果，第 i 条曲线可能相交的曲线序号为 `indices[offsets[i]..offsets[i + 1]]`
+#[repr(C)]
+pub struct IntersectionsCollisions {
+    pub offsets: *mut u32,
+    pub offsets_len: usize,
+    pub indices: *mut u32,
+    pub indices_len: usize,
+}
+
+fn endpoint_policy(policy: u32) -> Result<EndpointPolicy, i32> {
+    match policy {
+        INTERSECTIONS_INCLUDE_ALL => Ok(EndpointPolicy::IncludeAll),
+        INTERSECTIONS_EXCLUDE_SHARED => Ok(EndpointPolicy::ExcludeShared),
+        INTERSECTIONS_EXCLUDE_ALL => Ok(EndpointPolicy::ExcludeAll),
+        _ => Err(INTERSECTIONS_ERROR_INVALID_POLICY),
+    }
+}
+
+/// 将指针转换为曲线切片，`len` 为 0 时允许空指针
+unsafe fn curves<'a>(curves: *const f64, len: usize) -> &'a [Curve] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(curves as *const Curve, len)
+    }
+}
+
+/// 交出 `Vec` 的所有权，返回指针和长度
+fn into_raw<T>(values: Vec<T>) -> (*mut T, usize) {
+    let len = values.len();
+    if len == 0 {
+        return (ptr::null_mut(), 0);
+    }
+    (Box::into_raw(values.into_boxed_slice()) as *mut T, len)
+}
+
+/// 回收 `into_raw` 交出的内存
+unsafe fn free_raw<T>(data: *mut T, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+    }
+}
+
+unsafe fn locations(
+    curves1: *const f64,
+    len1: usize,
+    curves2: *const f64,
+    len2: usize,
+    is_self: bool,
+    policy: u32,
+) -> Result<Vec<[f64; 8]>, i32> {
+    let policy = endpoint_policy(policy)?;
+    let curves1 = curves(curves1, len1);
+    let curves2 = if is_self {
+        curves1
+    } else {
+        curves(curves2, len2)
+    };
+    let mut locations = Vec::new();
+    get_intersections_with_policy(curves1, curves2, is_self, policy, &mut locations);
+    Ok(locations)
+}
+
+/// 求两组曲线的相交点写入 `out`，`is_self` 时忽略 `curves2`
+///
+/// 失败时 `out` 为空结果，不需要释放
+///
+/// # Safety
+///
+/// `curves1`、`curves2` 分别指向 `len1 * 8`、`len2 * 8` 个有效的 `double`，`out` 指向可写的
+/// `IntersectionsLocations`
+#[no_mangle]
+pub unsafe extern "C" fn intersections_get(
+    curves1: *const f64,
+    len1: usize,
+    curves2: *const f64,
+    len2: usize,
+    is_self: bool,
+    policy: u32,
+    out: *mut IntersectionsLocations,
+) -> i32 {
+    *out = IntersectionsLocations {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+    let locations = match locations(curves1, len1, curves2, len2, is_self, policy) {
+        Ok(locations) => locations,
+        Err(code) => return code,
+    };
+    let (data, len) = into_raw(locations.concat());
+    *out = IntersectionsLocations { data, len: len / 8 };
+    INTERSECTIONS_OK
+}
+
+/// 求交并写入调用方提供的缓冲区，最多写入 `capacity` 个相交点，相交点总数写入 `total`
+///
+/// # Safety
+///
+/// 与 `intersections_get` 相同，`out` 指向 `capacity * 8` 个可写的 `double`，`total` 指向可写的 `size_t`
+#[no_mangle]
+pub unsafe extern "C" fn intersections_get_into(
+    curves1: *const f64,
+    len1: usize,
+    curves2: *const f64,
+    len2: usize,
+    is_self: bool,
+    policy: u32,
+    out: *mut f64,
+    capacity: usize,
+    total: *mut usize,
+) -> i32 {
+    *total = 0;
+    let locations = match locations(curves1, len1, curves2, len2, is_self, policy) {
+        Ok(locations) => locations,
+        Err(code) => return code,
+    };
+    let count = locations.len().min(capacity);
+    if count > 0 {
+        ptr::copy_nonoverlapping(locations.as_ptr() as *const f64, out, count * 8);
+    }
+    *total = locations.len();
+    INTERSECTIONS_OK
+}
+
+/// 释放 `intersections_get` 返回的结果
+///
+/// # Safety
+///
+/// `locations` 必须由 `intersections_get` 返回且只释放一次
+#[no_mangle]
+pub unsafe extern "C" fn intersections_locations_free(locations: IntersectionsLocations) {
+    free_raw(locations.data, locations.len * 8);
+}
+
+/// 切割曲线 `[t1, t2]` 部分，结果写入 `out`
+///
+/// # Safety
+///
+/// `v` 指向 8 个有效的 `double`，`out` 指向 8 个可写的 `double`
+#[no_mangle]
+pub unsafe extern "C" fn intersections_split_cubic_bezier_part(
+    v: *const f64,
+    t1: f64,
+    t2: f64,
+    out: *mut f64,
+) {
+    let part = split_cubic_bezier_part(&*(v as *const Curve), t1, t2);
+    ptr::copy_nonoverlapping(part.as_ptr(), out, 8);
+}
+
+/// 计算曲线上的点、切线、法线或曲率，`type_` 含义与 `evaluate` 相同，`t` 超出 `[0, 1]` 时返回 false
+///
+/// # Safety
+///
+/// `v` 指向 8 个有效的 `double`，`out` 指向 2 个可写的 `double`
+#[no_mangle]
+pub unsafe extern "C" fn intersections_evaluate(
+    v: *const f64,
+    t: f64,
+    type_: u8,
+    out: *mut f64,
+) -> bool {
+    match evaluate(slice::from_raw_parts(v, 8), t, type_) {
+        Some(point) => {
+            ptr::copy_nonoverlapping(point.as_ptr(), out, 2);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 检测两组曲线之间的边界碰撞，`is_self` 时忽略 `curves2`
+///
+/// # Safety
+///
+/// 与 `intersections_get` 相同
+#[no_mangle]
+pub unsafe extern "C" fn intersections_find_curve_bounds_collisions(
+    curves1: *const f64,
+    len1: usize,
+    curves2: *const f64,
+    len2: usize,
+    is_self: bool,
+    tolerance: f64,
+) -> IntersectionsCollisions {
+    let curves1 = curves(curves1, len1);
+    let curves2 = if is_self {
+        curves1
+    } else {
+        curves(curves2, len2)
+    };
+    let collisions = CollisionDetection::find_curve_bounds_collisions(
+        curves1,
+        curves2,
+        is_self,
+        tolerance,
+        Broadphase::default(),
+    );
+    let (offsets, offsets_len) = into_raw(collisions.offsets);
+    let (indices, indices_len) = into_raw(collisions.indices);
+    IntersectionsCollisions {
+        offsets,
+        offsets_len,
+        indices,
+        indices_len,
+    }
+}
+
+/// 释放 `intersections_find_curve_bounds_collisions` 返回的结果
+///
+/// # Safety
+///
+/// `collisions` 必须由 `intersections_find_curve_bounds_collisions` 返回且只释放一次
+#[no_mangle]
+pub unsafe extern "C" fn intersections_collisions_free(collisions: IntersectionsCollisions) {
+    free_raw(collisions.offsets, collisions.offsets_len);
+    free_raw(collisions.indices, collisions.indices_len);
+}
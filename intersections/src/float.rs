@@ -0,0 +1,393 @@
+use core::fmt::Debug;
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// 数学函数，启用 `std` 时使用标准库，否则使用 libm
+#[cfg(feature = "std")]
+mod math {
+    macro_rules! std_math {
+        ($t:ident { $($extra:item)* }) => {
+            pub mod $t {
+                pub fn abs(x: $t) -> $t {
+                    x.abs()
+                }
+                pub fn sqrt(x: $t) -> $t {
+                    x.sqrt()
+                }
+                pub fn powi(x: $t, n: i32) -> $t {
+                    x.powi(n)
+                }
+                pub fn powf(x: $t, n: $t) -> $t {
+                    x.powf(n)
+                }
+                pub fn cos(x: $t) -> $t {
+                    x.cos()
+                }
+                pub fn sin(x: $t) -> $t {
+                    x.sin()
+                }
+                pub fn acos(x: $t) -> $t {
+                    x.acos()
+                }
+                pub fn atan2(y: $t, x: $t) -> $t {
+                    y.atan2(x)
+                }
+                $($extra)*
+            }
+        };
+    }
+    std_math!(f64 {
+        pub fn round(x: f64) -> f64 {
+            x.round()
+        }
+        pub fn mul_add(x: f64, a: f64, b: f64) -> f64 {
+            x.mul_add(a, b)
+        }
+    });
+    std_math!(f32 {});
+}
+
+#[cfg(not(feature = "std"))]
+mod math {
+    pub mod f64 {
+        pub use libm::{acos, atan2, cos, fabs as abs, pow as powf, round, sin, sqrt};
+        pub fn powi(x: f64, n: i32) -> f64 {
+            libm::pow(x, n as f64)
+        }
+        pub fn mul_add(x: f64, a: f64, b: f64) -> f64 {
+            libm::fma(x, a, b)
+        }
+    }
+    pub mod f32 {
+        pub use libm::{
+            acosf as acos, atan2f as atan2, cosf as cos, fabsf as abs, powf, sinf as sin,
+            sqrtf as sqrt,
+        };
+        pub fn powi(x: f32, n: i32) -> f32 {
+            libm::powf(x, n as f32)
+        }
+    }
+}
+
+/// 浮点数精度抽象
+///
+/// 曲线计算中的容差随精度变化，`f64` 的取值与 paper.js 保持一致
+pub trait Float:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const INFINITY: Self;
+    const NEG_INFINITY: Self;
+    const GEOMETRIC_EPSILON: Self;
+    const CURVETIME_EPSILON: Self;
+    const FATLINE_EPSILON: Self;
+    const EPSILON: Self;
+    const MACHINE_EPSILON: Self;
+
+    fn from_f64(v: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn is_nan(self) -> bool;
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn powf(self, n: f64) -> Self;
+    fn cos(self) -> Self;
+    fn sin(self) -> Self;
+    fn acos(self) -> Self;
+    /// `self` 为 y 坐标
+    fn atan2(self, x: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn clamp(self, min: Self, max: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($t:ident, $geometric:expr, $curvetime:expr, $fatline:expr, $epsilon:expr, $machine:expr) => {
+        impl Float for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const INFINITY: Self = <$t>::INFINITY;
+            const NEG_INFINITY: Self = <$t>::NEG_INFINITY;
+            const GEOMETRIC_EPSILON: Self = $geometric;
+            const CURVETIME_EPSILON: Self = $curvetime;
+            const FATLINE_EPSILON: Self = $fatline;
+            const EPSILON: Self = $epsilon;
+            const MACHINE_EPSILON: Self = $machine;
+
+            fn from_f64(v: f64) -> Self {
+                v as $t
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+            fn abs(self) -> Self {
+                math::$t::abs(self)
+            }
+            fn sqrt(self) -> Self {
+                math::$t::sqrt(self)
+            }
+            fn powi(self, n: i32) -> Self {
+                math::$t::powi(self, n)
+            }
+            fn powf(self, n: f64) -> Self {
+                math::$t::powf(self, n as $t)
+            }
+            fn cos(self) -> Self {
+                math::$t::cos(self)
+            }
+            fn sin(self) -> Self {
+                math::$t::sin(self)
+            }
+            fn acos(self) -> Self {
+                math::$t::acos(self)
+            }
+            fn atan2(self, x: Self) -> Self {
+                math::$t::atan2(self, x)
+            }
+            fn min(self, other: Self) -> Self {
+                <$t>::min(self, other)
+            }
+            fn max(self, other: Self) -> Self {
+                <$t>::max(self, other)
+            }
+            fn clamp(self, min: Self, max: Self) -> Self {
+                <$t>::clamp(self, min, max)
+            }
+        }
+    };
+}
+
+impl_float!(f64, 1e-7, 1e-8, 1e-9, 1e-12, 1.12e-16);
+impl_float!(f32, 1e-4, 1e-3, 1e-5, 1e-5, 6e-8);
+
+/// 双倍精度浮点数（double-double），数值为 `hi + lo`
+///
+/// 约 106 位有效位，用于切线附近 `f64` 裁剪无法收敛的情况。
+/// `cos`、`sin`、`acos` 只有 `f64` 精度，`sqrt` 和开整数次方经过一次牛顿迭代修正
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+impl DoubleDouble {
+    pub const fn new(hi: f64, lo: f64) -> Self {
+        DoubleDouble { hi, lo }
+    }
+
+    pub fn hi(self) -> f64 {
+        self.hi
+    }
+
+    pub fn lo(self) -> f64 {
+        self.lo
+    }
+
+    /// 无误差加法 `a + b = s + e`
+    fn two_sum(a: f64, b: f64) -> Self {
+        let s = a + b;
+        let bb = s - a;
+        let e = (a - (s - bb)) + (b - bb);
+        DoubleDouble { hi: s, lo: e }
+    }
+
+    /// 无误差加法，要求 `|a| >= |b|`
+    fn quick_two_sum(a: f64, b: f64) -> Self {
+        let s = a + b;
+        DoubleDouble {
+            hi: s,
+            lo: b - (s - a),
+        }
+    }
+
+    /// 无误差乘法 `a * b = p + e`
+    fn two_prod(a: f64, b: f64) -> Self {
+        let p = a * b;
+        DoubleDouble {
+            hi: p,
+            lo: math::f64::mul_add(a, b, -p),
+        }
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(v: f64) -> Self {
+        DoubleDouble { hi: v, lo: 0.0 }
+    }
+}
+
+impl From<DoubleDouble> for f64 {
+    fn from(v: DoubleDouble) -> Self {
+        v.hi + v.lo
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let s = Self::two_sum(self.hi, rhs.hi);
+        let t = Self::two_sum(self.lo, rhs.lo);
+        let s = Self::quick_two_sum(s.hi, s.lo + t.hi);
+        Self::quick_two_sum(s.hi, s.lo + t.lo)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let p = Self::two_prod(self.hi, rhs.hi);
+        Self::quick_two_sum(p.hi, p.lo + (self.hi * rhs.lo + self.lo * rhs.hi))
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let q1 = self.hi / rhs.hi;
+        let r = self - rhs * DoubleDouble::from(q1);
+        let q2 = r.hi / rhs.hi;
+        let r = r - rhs * DoubleDouble::from(q2);
+        let q3 = r.hi / rhs.hi;
+        Self::quick_two_sum(q1, q2) + DoubleDouble::from(q3)
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+    fn neg(self) -> Self {
+        DoubleDouble {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl AddAssign for DoubleDouble {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for DoubleDouble {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Float for DoubleDouble {
+    const ZERO: Self = DoubleDouble::new(0.0, 0.0);
+    const ONE: Self = DoubleDouble::new(1.0, 0.0);
+    const INFINITY: Self = DoubleDouble::new(f64::INFINITY, 0.0);
+    const NEG_INFINITY: Self = DoubleDouble::new(f64::NEG_INFINITY, 0.0);
+    const GEOMETRIC_EPSILON: Self = DoubleDouble::new(1e-7, 0.0);
+    const CURVETIME_EPSILON: Self = DoubleDouble::new(1e-8, 0.0);
+    const FATLINE_EPSILON: Self = DoubleDouble::new(1e-12, 0.0);
+    const EPSILON: Self = DoubleDouble::new(1e-12, 0.0);
+    const MACHINE_EPSILON: Self = DoubleDouble::new(1e-30, 0.0);
+
+    fn from_f64(v: f64) -> Self {
+        DoubleDouble::from(v)
+    }
+    fn to_f64(self) -> f64 {
+        self.into()
+    }
+    fn is_nan(self) -> bool {
+        self.hi.is_nan()
+    }
+    fn abs(self) -> Self {
+        if self.hi < 0.0 {
+            -self
+        } else {
+            self
+        }
+    }
+    fn sqrt(self) -> Self {
+        if self.hi <= 0.0 {
+            return DoubleDouble::from(math::f64::sqrt(self.hi));
+        }
+        // 牛顿迭代一次 x + (a - x²) / 2x
+        let x = DoubleDouble::from(math::f64::sqrt(self.hi));
+        x + (self - x * x) / (x + x)
+    }
+    fn powi(self, n: i32) -> Self {
+        let mut result = Self::ONE;
+        for _ in 0..n.unsigned_abs() {
+            result = result * self;
+        }
+        if n < 0 {
+            Self::ONE / result
+        } else {
+            result
+        }
+    }
+    fn powf(self, n: f64) -> Self {
+        if n == 0.5 {
+            return self.sqrt();
+        }
+        if n == 1.5 {
+            return self * self.sqrt();
+        }
+        let y = DoubleDouble::from(math::f64::powf(self.hi, n));
+        let k = math::f64::round(1.0 / n);
+        if k >= 2.0 && math::f64::abs(1.0 / n - k) < f64::EPSILON && y.hi != 0.0 {
+            // 开 k 次方，牛顿迭代一次 y - (yᵏ - a) / (k yᵏ⁻¹)
+            let yk1 = y.powi(k as i32 - 1);
+            return y - (yk1 * y - self) / (DoubleDouble::from(k) * yk1);
+        }
+        y
+    }
+    fn cos(self) -> Self {
+        DoubleDouble::from(math::f64::cos(self.to_f64()))
+    }
+    fn sin(self) -> Self {
+        DoubleDouble::from(math::f64::sin(self.to_f64()))
+    }
+    fn acos(self) -> Self {
+        DoubleDouble::from(math::f64::acos(self.to_f64()))
+    }
+    fn atan2(self, x: Self) -> Self {
+        DoubleDouble::from(math::f64::atan2(self.to_f64(), x.to_f64()))
+    }
+    fn min(self, other: Self) -> Self {
+        if other.is_nan() || self < other {
+            self
+        } else {
+            other
+        }
+    }
+    fn max(self, other: Self) -> Self {
+        if other.is_nan() || self > other {
+            self
+        } else {
+            other
+        }
+    }
+    fn clamp(self, min: Self, max: Self) -> Self {
+        if self < min {
+            min
+        } else if self > max {
+            max
+        } else {
+            self
+        }
+    }
+}
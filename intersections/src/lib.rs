@@ -1,20 +1,78 @@
-extern crate wasm_bindgen;
-use wasm_bindgen::prelude::*;
+#![allow(
+    clippy::manual_range_contains,
+    clippy::nonminimal_bool,
+    clippy::too_many_arguments
+)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("需要启用 `std` 或 `libm` 特性");
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::mem::MaybeUninit;
+mod budget;
+mod bvh;
+mod clip;
 mod collision_detection;
-use collision_detection::CollisionDetection;
-const GEOMETRIC_EPSILON: f64 = 1e-7;
-const CURVETIME_EPSILON: f64 = 1e-8;
-const FATLINE_EPSILON: f64 = 1e-9;
-const EPSILON: f64 = 1e-12;
-const MACHINE_EPSILON: f64 = 1.12e-16;
+mod crossing;
+mod debug_svg;
+mod distance;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod float;
+mod location;
+mod ray;
+mod refine;
+mod scanline;
+mod self_intersection;
+mod similarity;
+mod stats;
+mod trace;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub use budget::{Budget, Deadline, IntersectionCursor, IntersectionJob, WorkBudget};
+pub use bvh::Bvh;
+pub use clip::{clip_to_grid, clip_to_rect, Tile, TileGrid};
+pub use collision_detection::{BoundsCollisions, Broadphase, CollisionDetection, RectSelection};
+pub use crossing::{get_crossing, Crossing};
+pub use debug_svg::render_debug_svg;
+pub use distance::{
+    find_within_distance, min_distance, min_path_distance, ClosestPoints, SearchTruncated,
+};
+pub use float::{DoubleDouble, Float};
+pub use location::{
+    location_crossings, location_geometries, Intersection, LocationGeometry, LocationKind,
+};
+pub use ray::{first_hit, intersect_infinite_line, intersect_ray, RayHit};
+pub use refine::{
+    refine_intersection, refine_locations, RefineStatus, RefinedLocation, Refinement,
+};
+pub use scanline::{hatch_lines, FillRule, Scanline, ScanlineCrossing, MAX_HATCH_LINES};
+pub use self_intersection::{get_self_intersection, SelfIntersection};
+pub use similarity::{
+    discrete_frechet_distance, frechet_distance, hausdorff_distance, project_point, Projection,
+    MAX_FRECHET_POINTS,
+};
+use stats::CurveBranch;
+pub use stats::Stats;
+pub use trace::{ClipDecision, ClipStep, ClipTrace, ClipTracer};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// 三次贝塞尔曲线 `[p1x, p1y, c1x, c1y, c2x, c2y, p2x, p2y]`
+pub type Curve<F = f64> = [F; 8];
 
 /// 分割贝塞尔曲线
-pub fn split_cubic_bezier(bez: &[f64; 8], t: f64) -> ([f64; 8], [f64; 8]) {
+pub fn split_cubic_bezier<F: Float>(bez: &Curve<F>, t: F) -> (Curve<F>, Curve<F>) {
     let (p1x, p1y, c1x, c1y, c2x, c2y, p2x, p2y) = (
         bez[0], bez[1], bez[2], bez[3], bez[4], bez[5], bez[6], bez[7],
     );
 
-    let u = 1.0 - t;
+    let u = F::ONE - t;
     let p3x = u * p1x + t * c1x;
     let p3y = u * p1y + t * c1y;
     let p4x = u * c1x + t * c2x;
@@ -35,75 +93,98 @@ pub fn split_cubic_bezier(bez: &[f64; 8], t: f64) -> ([f64; 8], [f64; 8]) {
 }
 
 /// 切割部分曲线[t1,t2]
-pub fn split_cubic_bezier_part(v: &[f64; 8], t1: f64, t2: f64) -> [f64; 8] {
-    let mut v_part = v.clone();
+pub fn split_cubic_bezier_part<F: Float>(v: &Curve<F>, t1: F, t2: F) -> Curve<F> {
+    let mut v_part = *v;
 
-    if t1 > 0.0 {
+    if t1 > F::ZERO {
         v_part = split_cubic_bezier(&v_part, t1).1;
     }
-    if t2 < 1.0 {
-        let t = (t2 - t1) / (1.0 - t1);
+    if t2 < F::ONE {
+        let t = (t2 - t1) / (F::ONE - t1);
         v_part = split_cubic_bezier(&v_part, t).0;
     }
 
     v_part
 }
 
+pub type Point<F = f64> = (F, F);
+
+/// 凸包的上半部分或下半部分，最多包含四个顶点
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hull<F = f64> {
+    points: [Point<F>; 4],
+    len: usize,
+}
+
+impl<F: Float> Hull<F> {
+    fn new(points: &[Point<F>]) -> Self {
+        let mut hull = Hull {
+            points: [(F::ZERO, F::ZERO); 4],
+            len: points.len(),
+        };
+        hull.points[..points.len()].copy_from_slice(points);
+        hull
+    }
+
+    pub fn points(&self) -> &[Point<F>] {
+        &self.points[..self.len]
+    }
+
+    /// 顶点逆序
+    fn reversed(&self) -> Self {
+        let mut hull = *self;
+        hull.points[..self.len].reverse();
+        hull
+    }
+}
+
 /// 计算贝塞尔凸包
-pub fn get_convex_hull(
-    dq0: f64,
-    dq1: f64,
-    dq2: f64,
-    dq3: f64,
-) -> (Vec<(f64, f64)>, Vec<(f64, f64)>) {
-    let p0 = (0.0, dq0);
-    let p1 = (1.0 / 3.0, dq1);
-    let p2 = (2.0 / 3.0, dq2);
-    let p3 = (1.0, dq3);
-    let dist1: f64 = dq1 - (2.0 * dq0 + dq3) / 3.0;
-    let dist2: f64 = dq2 - (dq0 + 2.0 * dq3) / 3.0;
-
-    let hull: (Vec<(f64, f64)>, Vec<(f64, f64)>);
-
-    if dist1 * dist2 < 0.0 {
+pub fn get_convex_hull<F: Float>(dq0: F, dq1: F, dq2: F, dq3: F) -> (Hull<F>, Hull<F>) {
+    let two = F::from_f64(2.0);
+    let three = F::from_f64(3.0);
+    let p0 = (F::ZERO, dq0);
+    let p1 = (F::from_f64(1.0 / 3.0), dq1);
+    let p2 = (F::from_f64(2.0 / 3.0), dq2);
+    let p3 = (F::ONE, dq3);
+    let dist1 = dq1 - (two * dq0 + dq3) / three;
+    let dist2 = dq2 - (dq0 + two * dq3) / three;
+
+    let hull = if dist1 * dist2 < F::ZERO {
         // 凸包包括两个三角形
-        hull = (vec![p0, p1, p3], vec![p0, p2, p3]);
+        (Hull::new(&[p0, p1, p3]), Hull::new(&[p0, p2, p3]))
     } else {
         let dist_ratio = dist1 / dist2;
 
-        if dist_ratio >= 2.0 {
+        if dist_ratio >= two {
             // 凸包包括一个三角形和一条线段
-            hull = (vec![p0, p1, p3], vec![p0, p3]);
-        } else if dist_ratio <= 0.5 {
+            (Hull::new(&[p0, p1, p3]), Hull::new(&[p0, p3]))
+        } else if dist_ratio <= F::from_f64(0.5) {
             // 凸包包括一个三角形和一条线段
-            hull = (vec![p0, p2, p3], vec![p0, p3]);
+            (Hull::new(&[p0, p2, p3]), Hull::new(&[p0, p3]))
         } else {
             // 凸包包括一个四边形和一条线段
-            hull = (vec![p0, p1, p2, p3], vec![p0, p3]);
+            (Hull::new(&[p0, p1, p2, p3]), Hull::new(&[p0, p3]))
         }
-    }
+    };
 
-    if dist1 > 0.0 {
+    if dist1 > F::ZERO {
         return hull;
     }
-    if dist1 < 0.0 {
-        return (hull.1, hull.0);
-    }
-    if dist2 < 0.0 {
+    if dist1 < F::ZERO || dist2 < F::ZERO {
         return (hull.1, hull.0);
     }
 
     hull
 }
 
-type Point = (f64, f64);
 /// 凸包裁剪
-fn clip_convex_hull(
-    hull_top: &[Point],
-    hull_bottom: &[Point],
-    d_min: f64,
-    d_max: f64,
-) -> Option<f64> {
+fn clip_convex_hull<F: Float>(
+    hull_top: &Hull<F>,
+    hull_bottom: &Hull<F>,
+    d_min: F,
+    d_max: F,
+) -> Option<F> {
+    let (hull_top, hull_bottom) = (hull_top.points(), hull_bottom.points());
     if hull_top[0].1 < d_min {
         clip_convex_hull_part(hull_top, true, d_min)
     } else if hull_bottom[0].1 > d_max {
@@ -113,7 +194,7 @@ fn clip_convex_hull(
     }
 }
 
-fn clip_convex_hull_part(part: &[Point], is_top: bool, threshold: f64) -> Option<f64> {
+fn clip_convex_hull_part<F: Float>(part: &[Point<F>], is_top: bool, threshold: F) -> Option<F> {
     let (mut prev_x, mut prev_y) = part[0];
     for &(current_x, current_y) in part.iter().skip(1) {
         if (is_top && current_y >= threshold) || (!is_top && current_y <= threshold) {
@@ -131,40 +212,36 @@ fn clip_convex_hull_part(part: &[Point], is_top: bool, threshold: f64) -> Option
 }
 
 /// Fat Line
-fn get_fatline(v: &[f64]) -> (f64, f64, f64, f64) {
+fn get_fatline<F: Float>(v: &[F]) -> (F, F, F, F) {
     let q0x = v[0];
     let q0y = v[1];
     let q3x = v[6];
     let q3y = v[7];
     let d1 = signed_distance(q0x, q0y, q3x, q3y, v[2], v[3], false);
     let d2 = signed_distance(q0x, q0y, q3x, q3y, v[4], v[5], false);
-    let factor = if d1 * d2 > 0.0 { 3.0 / 4.0 } else { 4.0 / 9.0 };
-    let d_min = factor * d1.min(d2).min(0.0);
-    let d_max = factor * d1.max(d2).max(0.0);
+    let factor = if d1 * d2 > F::ZERO {
+        F::from_f64(3.0 / 4.0)
+    } else {
+        F::from_f64(4.0 / 9.0)
+    };
+    let d_min = factor * d1.min(d2).min(F::ZERO);
+    let d_max = factor * d1.max(d2).max(F::ZERO);
     (d_min, d_max, d1, d2)
 }
 
-fn signed_distance(
-    px: f64,
-    py: f64,
-    mut vx: f64,
-    mut vy: f64,
-    x: f64,
-    y: f64,
-    as_vector: bool,
-) -> f64 {
+fn signed_distance<F: Float>(px: F, py: F, mut vx: F, mut vy: F, x: F, y: F, as_vector: bool) -> F {
     if !as_vector {
         vx -= px;
         vy -= py;
     }
-    let result = if vx == 0.0 {
-        if vy > 0.0 {
+    if vx == F::ZERO {
+        if vy > F::ZERO {
             x - px
         } else {
             px - x
         }
-    } else if vy == 0.0 {
-        if vx < 0.0 {
+    } else if vy == F::ZERO {
+        if vx < F::ZERO {
             y - py
         } else {
             py - y
@@ -172,20 +249,19 @@ fn signed_distance(
     } else {
         let dist = (x - px) * vy - (y - py) * vx;
         let denom = if vy > vx {
-            vy * (1.0 + (vx * vx) / (vy * vy)).sqrt()
+            vy * (F::ONE + (vx * vx) / (vy * vy)).sqrt()
         } else {
-            vx * (1.0 + (vy * vy) / (vx * vx)).sqrt()
+            vx * (F::ONE + (vy * vy) / (vx * vx)).sqrt()
         };
         dist / denom
-    };
-    result
+    }
 }
 
-fn is_zero(val: f64) -> bool {
-    val >= -EPSILON && val <= EPSILON
+fn is_zero<F: Float>(val: F) -> bool {
+    val >= -F::EPSILON && val <= F::EPSILON
 }
-fn is_curve_zero(val: f64) -> bool {
-    val >= -CURVETIME_EPSILON && val <= CURVETIME_EPSILON
+fn is_curve_zero<F: Float>(val: F) -> bool {
+    val >= -F::CURVETIME_EPSILON && val <= F::CURVETIME_EPSILON
 }
 /// 计算贝塞尔曲线上的点、切线、法线和曲率
 ///
@@ -193,8 +269,8 @@ fn is_curve_zero(val: f64) -> bool {
 /// type = 1时，计算曲线上的切线
 /// type = 2时，计算曲线上的法线
 /// type = 3时，计算曲线上的曲率
-pub fn evaluate(v: &[f64], t: f64, type_: u8) -> Option<[f64; 2]> {
-    if t.is_nan() || t < 0.0 || t > 1.0 {
+pub fn evaluate<F: Float>(v: &[F], t: F, type_: u8) -> Option<[F; 2]> {
+    if t.is_nan() || t < F::ZERO || t > F::ONE {
         return None;
     }
     let (x0, y0, mut x1, mut y1, mut x2, mut y2, x3, y3) =
@@ -209,11 +285,13 @@ pub fn evaluate(v: &[f64], t: f64, type_: u8) -> Option<[f64; 2]> {
         y2 = y3;
     }
 
-    let cx = 3.0 * (x1 - x0);
-    let bx = 3.0 * (x2 - x1) - cx;
+    let two = F::from_f64(2.0);
+    let three = F::from_f64(3.0);
+    let cx = three * (x1 - x0);
+    let bx = three * (x2 - x1) - cx;
     let ax = x3 - x0 - cx - bx;
-    let cy = 3.0 * (y1 - y0);
-    let by = 3.0 * (y2 - y1) - cy;
+    let cy = three * (y1 - y0);
+    let by = three * (y2 - y1) - cy;
     let ay = y3 - y0 - cy - by;
 
     let mut x;
@@ -221,42 +299,47 @@ pub fn evaluate(v: &[f64], t: f64, type_: u8) -> Option<[f64; 2]> {
 
     match type_ {
         0 => {
-            x = if t == 0.0 {
+            x = if t == F::ZERO {
                 x0
-            } else if t == 1.0 {
+            } else if t == F::ONE {
                 x3
             } else {
                 ((ax * t + bx) * t + cx) * t + x0
             };
-            y = if t == 0.0 {
+            y = if t == F::ZERO {
                 y0
-            } else if t == 1.0 {
+            } else if t == F::ONE {
                 y3
             } else {
                 ((ay * t + by) * t + cy) * t + y0
             };
         }
         _ => {
-            let t_min = CURVETIME_EPSILON;
-            let t_max = 1.0 - t_min;
+            let t_min = F::CURVETIME_EPSILON;
+            let t_max = F::ONE - t_min;
 
             if t < t_min {
                 x = cx;
                 y = cy;
             } else if t > t_max {
-                x = 3.0 * (x3 - x2);
-                y = 3.0 * (y3 - y2);
+                x = three * (x3 - x2);
+                y = three * (y3 - y2);
             } else {
-                x = (3.0 * ax * t + 2.0 * bx) * t + cx;
-                y = (3.0 * ay * t + 2.0 * by) * t + cy;
+                x = (three * ax * t + two * bx) * t + cx;
+                y = (three * ay * t + two * by) * t + cy;
             }
 
             if type_ == 3 {
-                let x2 = 6.0 * ax * t + 2.0 * bx;
-                let y2 = 6.0 * ay * t + 2.0 * by;
+                let six = F::from_f64(6.0);
+                let x2 = six * ax * t + two * bx;
+                let y2 = six * ay * t + two * by;
                 let d = (x * x + y * y).powf(1.5);
-                x = if d != 0.0 { (x * y2 - y * x2) / d } else { 0.0 };
-                y = 0.0;
+                x = if d != F::ZERO {
+                    (x * y2 - y * x2) / d
+                } else {
+                    F::ZERO
+                };
+                y = F::ZERO;
             }
         }
     }
@@ -267,21 +350,21 @@ pub fn evaluate(v: &[f64], t: f64, type_: u8) -> Option<[f64; 2]> {
     }
 }
 
-fn is_machine_zero(val: f64) -> bool {
-    val >= -MACHINE_EPSILON && val <= MACHINE_EPSILON
+fn is_machine_zero<F: Float>(val: F) -> bool {
+    val >= -F::MACHINE_EPSILON && val <= F::MACHINE_EPSILON
 }
 
 /// 计算直线相交
-pub fn line_intersection(
-    p1x: f64,
-    p1y: f64,
-    mut v1x: f64,
-    mut v1y: f64,
-    p2x: f64,
-    p2y: f64,
-    mut v2x: f64,
-    mut v2y: f64,
-) -> Option<(f64, f64)> {
+pub fn line_intersection<F: Float>(
+    p1x: F,
+    p1y: F,
+    mut v1x: F,
+    mut v1y: F,
+    p2x: F,
+    p2y: F,
+    mut v2x: F,
+    mut v2y: F,
+) -> Option<(F, F)> {
     v1x -= p1x;
     v1y -= p1y;
     v2x -= p2x;
@@ -292,44 +375,39 @@ pub fn line_intersection(
         let dy = p1y - p2y;
         let u1 = (v2x * dy - v2y * dx) / cross;
         let u2 = (v1x * dy - v1y * dx) / cross;
-        let epsilon = MACHINE_EPSILON;
+        let epsilon = F::MACHINE_EPSILON;
         let u_min = -epsilon;
-        let u_max = 1.0 + epsilon;
+        let u_max = F::ONE + epsilon;
         if u_min < u1 && u1 < u_max && u_min < u2 && u2 < u_max {
-            let t = if u1 <= 0.0 {
-                0.0
-            } else if u1 >= 1.0 {
-                1.0
-            } else {
-                u1
-            };
+            let t = u1.clamp(F::ZERO, F::ONE);
             return Some((p1x + t * v1x, p1y + t * v1y));
         }
     }
     None
 }
 
-fn bezier_coeffs(p0: f64, p1: f64, p2: f64, p3: f64) -> [f64; 4] {
+pub(crate) fn bezier_coeffs<F: Float>(p0: F, p1: F, p2: F, p3: F) -> [F; 4] {
+    let three = F::from_f64(3.0);
     [
-        -p0 + 3.0 * p1 - 3.0 * p2 + p3,
-        3.0 * p0 - 6.0 * p1 + 3.0 * p2,
-        -3.0 * p0 + 3.0 * p1,
+        -p0 + three * p1 - three * p2 + p3,
+        three * p0 - F::from_f64(6.0) * p1 + three * p2,
+        -three * p0 + three * p1,
         p0,
     ]
 }
-fn sgn(x: f64) -> f64 {
-    if x < 0.0 {
-        -1.0
+fn sgn<F: Float>(x: F) -> F {
+    if x < F::ZERO {
+        -F::ONE
     } else {
-        1.0
+        F::ONE
     }
 }
-fn sort_special(mut a: Vec<f64>) -> Vec<f64> {
+fn sort_special<F: Float>(mut a: [F; 3]) -> [F; 3] {
     let mut flipped = true;
     while flipped {
         flipped = false;
         for i in 0..a.len() - 1 {
-            if (a[i + 1] >= 0.0 && a[i] > a[i + 1]) || (a[i] < 0.0 && a[i + 1] >= 0.0) {
+            if (a[i + 1] >= F::ZERO && a[i] > a[i + 1]) || (a[i] < F::ZERO && a[i + 1] >= F::ZERO) {
                 a.swap(i, i + 1);
                 flipped = true;
             }
@@ -337,31 +415,32 @@ fn sort_special(mut a: Vec<f64>) -> Vec<f64> {
     }
     a
 }
-fn cubic_roots(p: &[f64; 4]) -> Vec<f64> {
-    if p[0] == 0.0 {
-        if p[1] == 0.0 {
-            let mut t = vec![-1.0; 3];
-            t[0] = -1.0 * (p[3] / p[2]);
-            t[1] = -1.0;
-            t[2] = -1.0;
-            if t[0] < 0.0 || t[0] > 1.0 || t[0].is_nan() {
-                t[0] = -1.0;
+/// 求解三次方程在 [0,1] 内的实根，无效的根以 -1 表示并排在最后
+fn cubic_roots<F: Float>(p: &[F; 4]) -> [F; 3] {
+    let none = -F::ONE;
+    let two = F::from_f64(2.0);
+    let three = F::from_f64(3.0);
+    if p[0] == F::ZERO {
+        if p[1] == F::ZERO {
+            let mut t = [none; 3];
+            t[0] = -(p[3] / p[2]);
+            if t[0] < F::ZERO || t[0] > F::ONE || t[0].is_nan() {
+                t[0] = none;
             }
             return sort_special(t);
         }
 
-        let mut dq = p[2] * p[2] - 4.0 * p[1] * p[3];
-        if dq >= 0.0 {
+        let mut dq = p[2] * p[2] - F::from_f64(4.0) * p[1] * p[3];
+        if dq >= F::ZERO {
             dq = dq.sqrt();
-            let mut t = vec![-1.0; 3];
-            t[0] = -1.0 * ((dq + p[2]) / (2.0 * p[1]));
-            t[1] = (dq - p[2]) / (2.0 * p[1]);
-            t[2] = -1.0;
-            if t[0] < 0.0 || t[0] > 1.0 || t[0].is_nan() {
-                t[0] = -1.0;
+            let mut t = [none; 3];
+            t[0] = -((dq + p[2]) / (two * p[1]));
+            t[1] = (dq - p[2]) / (two * p[1]);
+            if t[0] < F::ZERO || t[0] > F::ONE || t[0].is_nan() {
+                t[0] = none;
             }
-            if t[1] < 0.0 || t[1] > 1.0 || t[1].is_nan() {
-                t[1] = -1.0;
+            if t[1] < F::ZERO || t[1] > F::ONE || t[1].is_nan() {
+                t[1] = none;
             }
             return sort_special(t);
         }
@@ -370,46 +449,48 @@ fn cubic_roots(p: &[f64; 4]) -> Vec<f64> {
     let b = p[2] / p[0];
     let c = p[3] / p[0];
 
-    let q = (3.0 * b - a * a) / 9.0;
-    let r = (9.0 * a * b - 27.0 * c - 2.0 * a * a * a) / 54.0;
+    let q = (three * b - a * a) / F::from_f64(9.0);
+    let r =
+        (F::from_f64(9.0) * a * b - F::from_f64(27.0) * c - two * a * a * a) / F::from_f64(54.0);
     let d = q * q * q + r * r;
 
-    let mut t = vec![-1.0; 3];
+    let mut t = [none; 3];
 
-    if d >= 0.0 {
+    if d >= F::ZERO {
         let d_sqrt = d.sqrt();
         let s = sgn(r + d_sqrt) * (r + d_sqrt).abs().powf(1.0 / 3.0);
         let t_val = sgn(r - d_sqrt) * (r - d_sqrt).abs().powf(1.0 / 3.0);
 
-        t[0] = -a / 3.0 + (s + t_val); // real root
-        t[1] = -a / 3.0 - (s + t_val) / 2.0; // real part of complex root
-        t[2] = -a / 3.0 - (s + t_val) / 2.0; // real part of complex root
-        let im = ((3.0_f64).sqrt() * (s - t_val)) / 2.0; // complex part of root pair
+        t[0] = -a / three + (s + t_val); // real root
+        t[1] = -a / three - (s + t_val) / two; // real part of complex root
+        t[2] = -a / three - (s + t_val) / two; // real part of complex root
+        let im = (three.sqrt() * (s - t_val)) / two; // complex part of root pair
 
-        if im != 0.0 {
-            t[1] = -1.0;
-            t[2] = -1.0;
+        if im != F::ZERO {
+            t[1] = none;
+            t[2] = none;
         }
     } else {
+        let pi = F::from_f64(core::f64::consts::PI);
         let q_sqrt = (-q).sqrt();
         let q_t = (-q.powi(3)).sqrt();
         let th = (r / q_t).acos();
 
-        t[0] = 2.0 * q_sqrt * (th / 3.0).cos() - a / 3.0;
-        t[1] = 2.0 * q_sqrt * ((th + 2.0 * std::f64::consts::PI) / 3.0).cos() - a / 3.0;
-        t[2] = 2.0 * q_sqrt * ((th + 4.0 * std::f64::consts::PI) / 3.0).cos() - a / 3.0;
+        t[0] = two * q_sqrt * (th / three).cos() - a / three;
+        t[1] = two * q_sqrt * ((th + two * pi) / three).cos() - a / three;
+        t[2] = two * q_sqrt * ((th + F::from_f64(4.0) * pi) / three).cos() - a / three;
     }
 
-    for i in 0..3 {
-        if t[i] < 0.0 || t[i] > 1.0 {
-            t[i] = -1.0;
+    for root in t.iter_mut() {
+        if *root < F::ZERO || *root > F::ONE {
+            *root = none;
         }
     }
 
     sort_special(t)
 }
 
-fn calculate_t_value(x1: f64, y1: f64, x2: f64, y2: f64, x: f64, y: f64) -> f64 {
+fn calculate_t_value<F: Float>(x1: F, y1: F, x2: F, y2: F, x: F, y: F) -> F {
     // Calculate the dot product of vectors (x2 - x1, y2 - y1) and (x - x1, y - y1)
     let dot_product = (x - x1) * (x2 - x1) + (y - y1) * (y2 - y1);
 
@@ -417,23 +498,19 @@ fn calculate_t_value(x1: f64, y1: f64, x2: f64, y2: f64, x: f64, y: f64) -> f64
     let line_length_squared = (x2 - x1).powi(2) + (y2 - y1).powi(2);
 
     // Calculate the value of parameter t, which is the position of point (x, y) on the line
-    let t = dot_product / line_length_squared;
-
-    t
+    dot_product / line_length_squared
 }
 
-/// 计算直线和曲线相交
-fn line_and_curve_intersection(v: &[f64], line: &[f64]) -> Vec<(f64, f64, f64, f64, f64, f64)> {
-    let px = [v[0], v[2], v[4], v[6]];
-    let py = [v[1], v[3], v[5], v[7]];
+/// 曲线与 `line` 所在的无限长直线相交的参数，只保留 [0, 1] 内的根，无效的根以 -1 表示
+fn line_and_curve_roots<F: Float>(v: &[F], line: &[F]) -> [F; 3] {
     let lx = [line[0], line[2]];
     let ly = [line[1], line[3]];
     let a = ly[1] - ly[0]; // A = y2 - y1
     let b = lx[0] - lx[1]; // B = x1 - x2
     let c = lx[0] * (ly[0] - ly[1]) + ly[0] * (lx[1] - lx[0]); // C = x1 * (y1 - y2) + y1 * (x2 - x1)
 
-    let bx = bezier_coeffs(px[0], px[1], px[2], px[3]); // Implement this function
-    let by = bezier_coeffs(py[0], py[1], py[2], py[3]); // Implement this function
+    let bx = bezier_coeffs(v[0], v[2], v[4], v[6]);
+    let by = bezier_coeffs(v[1], v[3], v[5], v[7]);
 
     let p = [
         a * bx[0] + b * by[0],     // t^3
@@ -442,23 +519,35 @@ fn line_and_curve_intersection(v: &[f64], line: &[f64]) -> Vec<(f64, f64, f64, f
         a * bx[3] + b * by[3] + c, // 1
     ];
 
-    let r = cubic_roots(&p);
+    cubic_roots(&p)
+}
+
+/// 计算直线和曲线相交
+fn line_and_curve_intersection<F: Float>(v: &[F], line: &[F]) -> Vec<(F, F, F, F, F, F)> {
+    let px = [v[0], v[2], v[4], v[6]];
+    let py = [v[1], v[3], v[5], v[7]];
+    let lx = [line[0], line[2]];
+    let ly = [line[1], line[3]];
+
+    let bx = bezier_coeffs(px[0], px[1], px[2], px[3]); // Implement this function
+    let by = bezier_coeffs(py[0], py[1], py[2], py[3]); // Implement this function
+
+    let r = line_and_curve_roots(v, line);
     let mut res = Vec::new();
     for &t in &r {
-        if t < 0.0 || t > 1.0 || t.is_nan() {
+        if t < F::ZERO || t > F::ONE || t.is_nan() {
             continue;
         }
         let intersection_x = bx[0] * t.powi(3) + bx[1] * t.powi(2) + bx[2] * t + bx[3];
         let intersection_y = by[0] * t.powi(3) + by[1] * t.powi(2) + by[2] * t + by[3];
 
-        let s: f64;
-        if lx[1] != lx[0] {
-            s = (intersection_x - lx[0]) / (lx[1] - lx[0]);
+        let s = if lx[1] != lx[0] {
+            (intersection_x - lx[0]) / (lx[1] - lx[0])
         } else {
-            s = (intersection_y - ly[0]) / (ly[1] - ly[0]);
-        }
+            (intersection_y - ly[0]) / (ly[1] - ly[0])
+        };
 
-        if s < 0.0 || s > 1.0 || s.is_nan() {
+        if s < F::ZERO || s > F::ONE || s.is_nan() {
             continue;
         }
 
@@ -470,11 +559,6 @@ fn line_and_curve_intersection(v: &[f64], line: &[f64]) -> Vec<(f64, f64, f64, f
             intersection_x,
             intersection_y,
         );
-        if t <= CURVETIME_EPSILON || t >= 1.0 - CURVETIME_EPSILON {
-            if line_t <= CURVETIME_EPSILON && line_t >= 1.0 - CURVETIME_EPSILON {
-                continue;
-            }
-        }
         res.push((
             t,
             intersection_x,
@@ -487,155 +571,287 @@ fn line_and_curve_intersection(v: &[f64], line: &[f64]) -> Vec<(f64, f64, f64, f
     res
 }
 
-fn bezier_intersections(
-    v1: &[f64; 8],
-    v2: &[f64; 8],
-    c1: &[f64; 8],
-    c2: &[f64; 8],
-    i1: f64,
-    i2: f64,
-    locations: &mut Vec<[f64; 8]>,
+/// 裁剪迭代的最大次数
+const MAX_CALLS: u16 = 4096;
+/// 裁剪迭代的最大深度
+const MAX_RECURSION: u8 = 40;
+/// 工作栈容量，深度优先时栈中最多为每层保留一个待处理的兄弟节点
+const STACK_SIZE: usize = MAX_RECURSION as usize + 2;
+
+/// 待裁剪的一对曲线片段，`v1` 的参数范围是 `[t_min, t_max]`，`v2` 的参数范围是 `[u_min, u_max]`
+#[derive(Clone, Copy)]
+struct ClipFrame<F> {
+    v1: Curve<F>,
+    v2: Curve<F>,
     flip: bool,
-    mut recursion: u8,
-    mut calls: u16,
-    t_min: f64,
-    t_max: f64,
-    u_min: f64,
-    u_max: f64,
-) -> u16 {
-    calls += 1;
-    recursion += 1;
-    if calls >= 4096 || recursion >= 40 {
-        return calls;
-    }
-
-    let fat_line_epsilon = FATLINE_EPSILON;
-    let (q0x, q0y, q3x, q3y) = (v2[0], v2[1], v2[6], v2[7]);
-    let (d_min, d_max, d1, d2) = get_fatline(v2);
-    let dp0 = signed_distance(q0x, q0y, q3x, q3y, v1[0], v1[1], false);
-    let dp1 = signed_distance(q0x, q0y, q3x, q3y, v1[2], v1[3], false);
-    let dp2 = signed_distance(q0x, q0y, q3x, q3y, v1[4], v1[5], false);
-    let dp3 = signed_distance(q0x, q0y, q3x, q3y, v1[6], v1[7], false);
-    let (top, bottom) = get_convex_hull(dp0, dp1, dp2, dp3);
-
-    if d1 == 0.0 && d2 == 0.0 && dp0 == 0.0 && dp1 == 0.0 && dp2 == 0.0 && dp3 == 0.0 {
-        return calls;
-    }
-
-    let t_min_clip = clip_convex_hull(&top, &bottom, d_min, d_max);
-    let t_max_clip = clip_convex_hull(
-        &top.iter().rev().cloned().collect::<Vec<_>>(),
-        &bottom.iter().rev().cloned().collect::<Vec<_>>(),
-        d_min,
-        d_max,
-    );
+    recursion: u8,
+    t_min: F,
+    t_max: F,
+    u_min: F,
+    u_max: F,
+}
 
-    let t_min_clip = match t_min_clip {
-        Some(clip) => clip,
-        None => return calls,
-    };
+impl<F: Float> ClipFrame<F> {
+    /// 交换两条曲线的角色
+    fn swapped(
+        v1: Curve<F>,
+        v2: Curve<F>,
+        parent: &ClipFrame<F>,
+        (t_min, t_max): (F, F),
+        (u_min, u_max): (F, F),
+    ) -> Self {
+        ClipFrame {
+            v1,
+            v2,
+            flip: !parent.flip,
+            recursion: parent.recursion,
+            t_min,
+            t_max,
+            u_min,
+            u_max,
+        }
+    }
 
-    let t_max_clip = match t_max_clip {
-        Some(clip) => clip,
-        None => return calls,
-    };
+    /// 当前片段的跟踪记录，裁剪的中间结果待填充
+    fn step(&self, call: u16) -> ClipStep<F> {
+        ClipStep {
+            call,
+            recursion: self.recursion,
+            flip: self.flip,
+            v1: self.v1,
+            v2: self.v2,
+            t_range: [self.t_min, self.t_max],
+            u_range: [self.u_min, self.u_max],
+            fatline: None,
+            hull: None,
+            clip: None,
+            decision: ClipDecision::MaxCalls,
+        }
+    }
+}
 
-    let t_min_new = t_min + (t_max - t_min) * t_min_clip;
-    let t_max_new = t_min + (t_max - t_min) * t_max_clip;
-
-    if (u_max - u_min).max(t_max_new - t_min_new) < fat_line_epsilon {
-        let t = (t_min_new + t_max_new) / 2.0;
-        let u = (u_min + u_max) / 2.0;
-        let (t1, t2) = if flip { (u, t) } else { (t, u) };
-        let (cc1, cc2) = if flip { (c2, c1) } else { (c1, c2) };
-        if (is_curve_zero(t1) && is_curve_zero(t2))
-            || (is_curve_zero(1.0 - t1) && is_curve_zero(1.0 - t2))
-            || (is_curve_zero(t1) && is_curve_zero(1.0 - t2))
-            || (is_curve_zero(1.0 - t1) && is_curve_zero(t2))
-        {
-            return calls;
+/// 固定容量的工作栈，避免递归和堆分配
+///
+/// 只有前 `len` 个位置已写入，其余位置不初始化，创建时不复制整个数组。
+/// 深度限制保证栈不会超过 `STACK_SIZE`，万一超出时多余的片段放到 `overflow`，不会丢失
+struct ClipStack<F> {
+    frames: [MaybeUninit<ClipFrame<F>>; STACK_SIZE],
+    len: usize,
+    overflow: Vec<ClipFrame<F>>,
+}
+
+impl<F: Float> ClipStack<F> {
+    fn new(frame: ClipFrame<F>) -> Self {
+        let mut stack = ClipStack {
+            frames: [MaybeUninit::uninit(); STACK_SIZE],
+            len: 0,
+            overflow: vec![],
+        };
+        stack.push(frame);
+        stack
+    }
+
+    fn push(&mut self, frame: ClipFrame<F>) {
+        if self.len < STACK_SIZE {
+            self.frames[self.len].write(frame);
+            self.len += 1;
+        } else {
+            self.overflow.push(frame);
         }
+    }
 
-        if let Some([x1, y1]) = evaluate(cc1, t1, 0) {
-            if let Some([x2, y2]) = evaluate(cc2, t2, 0) {
-                locations.push([t1, i1, x1, y1, t2, i2, x2, y2])
+    fn pop(&mut self) -> Option<ClipFrame<F>> {
+        // 溢出的片段在栈顶
+        if let Some(frame) = self.overflow.pop() {
+            return Some(frame);
+        }
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: 前 `len` 个位置都由 `push` 写入过
+        Some(unsafe { self.frames[self.len].assume_init() })
+    }
+}
+
+/// 贝塞尔裁剪求交
+///
+/// 用显式工作栈代替递归，深度优先的处理顺序与递归版本一致，返回裁剪次数和最大深度。
+/// 每一步交给 `tracer` 记录
+fn bezier_intersections<F: Float, T: ClipTracer<F>>(
+    v1: &Curve<F>,
+    v2: &Curve<F>,
+    i1: F,
+    i2: F,
+    locations: &mut Vec<[F; 8]>,
+    tracer: &mut T,
+) -> (u16, u8) {
+    let mut calls = 0;
+    let mut max_recursion = 0;
+    let mut stack = ClipStack::new(ClipFrame {
+        v1: *v1,
+        v2: *v2,
+        flip: false,
+        recursion: 0,
+        t_min: F::ZERO,
+        t_max: F::ONE,
+        u_min: F::ZERO,
+        u_max: F::ONE,
+    });
+    let one = F::ONE;
+
+    while let Some(mut frame) = stack.pop() {
+        calls += 1;
+        frame.recursion += 1;
+        max_recursion = max_recursion.max(frame.recursion);
+        let mut step = frame.step(calls);
+        if calls >= MAX_CALLS {
+            tracer.step(&step);
+            break;
+        }
+        if frame.recursion >= MAX_RECURSION {
+            tracer.step(&step.decided(ClipDecision::MaxRecursion));
+            continue;
+        }
+        let ClipFrame {
+            v1: fv1,
+            v2: fv2,
+            flip,
+            t_min,
+            t_max,
+            u_min,
+            u_max,
+            ..
+        } = frame;
+
+        let (q0x, q0y, q3x, q3y) = (fv2[0], fv2[1], fv2[6], fv2[7]);
+        let (d_min, d_max, d1, d2) = get_fatline(&fv2);
+        let dp0 = signed_distance(q0x, q0y, q3x, q3y, fv1[0], fv1[1], false);
+        let dp1 = signed_distance(q0x, q0y, q3x, q3y, fv1[2], fv1[3], false);
+        let dp2 = signed_distance(q0x, q0y, q3x, q3y, fv1[4], fv1[5], false);
+        let dp3 = signed_distance(q0x, q0y, q3x, q3y, fv1[6], fv1[7], false);
+        step.fatline = Some([d_min, d_max]);
+
+        let zero = F::ZERO;
+        if d1 == zero && d2 == zero && dp0 == zero && dp1 == zero && dp2 == zero && dp3 == zero {
+            tracer.step(&step.decided(ClipDecision::Collinear));
+            continue;
+        }
+
+        let (top, bottom) = get_convex_hull(dp0, dp1, dp2, dp3);
+        step.hull = Some((top, bottom));
+        let clips = clip_convex_hull(&top, &bottom, d_min, d_max).and_then(|t_min_clip| {
+            clip_convex_hull(&top.reversed(), &bottom.reversed(), d_min, d_max)
+                .map(|t_max_clip| (t_min_clip, t_max_clip))
+        });
+        let (t_min_clip, t_max_clip) = match clips {
+            Some(clips) => clips,
+            None => {
+                tracer.step(&step.decided(ClipDecision::Miss));
+                continue;
+            }
+        };
+        step.clip = Some([t_min_clip, t_max_clip]);
+
+        let t_min_new = t_min + (t_max - t_min) * t_min_clip;
+        let t_max_new = t_min + (t_max - t_min) * t_max_clip;
+
+        if (u_max - u_min).max(t_max_new - t_min_new) < F::FATLINE_EPSILON {
+            let two = F::from_f64(2.0);
+            let t = (t_min_new + t_max_new) / two;
+            let u = (u_min + u_max) / two;
+            let (t1, t2) = if flip { (u, t) } else { (t, u) };
+            let mut reported = false;
+            if !((is_curve_zero(t1) && is_curve_zero(t2))
+                || (is_curve_zero(one - t1) && is_curve_zero(one - t2))
+                || (is_curve_zero(t1) && is_curve_zero(one - t2))
+                || (is_curve_zero(one - t1) && is_curve_zero(t2)))
+            {
+                if let (Some([x1, y1]), Some([x2, y2])) = (evaluate(v1, t1, 0), evaluate(v2, t2, 0))
+                {
+                    locations.push([t1, i1, x1, y1, t2, i2, x2, y2]);
+                    reported = true;
+                }
             }
+            tracer.step(&step.decided(ClipDecision::Converged { reported }));
+            continue;
         }
-    } else {
-        let v1 = split_cubic_bezier_part(v1, t_min_clip, t_max_clip);
+
+        let part = split_cubic_bezier_part(&fv1, t_min_clip, t_max_clip);
+        let u_range = (u_min, u_max);
         let u_diff = u_max - u_min;
+        let two = F::from_f64(2.0);
+        let half = F::from_f64(0.5);
 
-        if t_max_clip - t_min_clip > 0.8 {
+        // 先压入后处理的片段，保持与递归版本相同的遍历顺序
+        if t_max_clip - t_min_clip > F::from_f64(0.8) {
             if t_max_new - t_min_new > u_diff {
-                let parts = split_cubic_bezier(&v1, 0.5);
-                let t = (t_min_new + t_max_new) / 2.0;
-                calls = bezier_intersections(
-                    v2, &parts.0, c2, c1, i1, i2, locations, !flip, recursion, calls, u_min, u_max,
-                    t_min_new, t,
-                );
-                calls = bezier_intersections(
-                    v2, &parts.1, c2, c1, i1, i2, locations, !flip, recursion, calls, u_min, u_max,
-                    t, t_max_new,
-                );
+                tracer.step(&step.decided(ClipDecision::SplitClipped));
+                let parts = split_cubic_bezier(&part, half);
+                let t = (t_min_new + t_max_new) / two;
+                stack.push(ClipFrame::swapped(
+                    fv2,
+                    parts.1,
+                    &frame,
+                    u_range,
+                    (t, t_max_new),
+                ));
+                stack.push(ClipFrame::swapped(
+                    fv2,
+                    parts.0,
+                    &frame,
+                    u_range,
+                    (t_min_new, t),
+                ));
             } else {
-                let parts = split_cubic_bezier(v2, 0.5);
-                let u = (u_min + u_max) / 2.0;
-                calls = bezier_intersections(
-                    &parts.0, &v1, c2, c1, i1, i2, locations, !flip, recursion, calls, u_min, u,
-                    t_min_new, t_max_new,
-                );
-                calls = bezier_intersections(
-                    &parts.1, &v1, c2, c1, i1, i2, locations, !flip, recursion, calls, u, u_max,
-                    t_min_new, t_max_new,
-                );
+                tracer.step(&step.decided(ClipDecision::SplitFatline));
+                let parts = split_cubic_bezier(&fv2, half);
+                let u = (u_min + u_max) / two;
+                let t_range = (t_min_new, t_max_new);
+                stack.push(ClipFrame::swapped(
+                    parts.1,
+                    part,
+                    &frame,
+                    (u, u_max),
+                    t_range,
+                ));
+                stack.push(ClipFrame::swapped(
+                    parts.0,
+                    part,
+                    &frame,
+                    (u_min, u),
+                    t_range,
+                ));
             }
+        } else if u_diff == F::ZERO || u_diff >= F::FATLINE_EPSILON {
+            tracer.step(&step.decided(ClipDecision::Swap));
+            let t_range = (t_min_new, t_max_new);
+            stack.push(ClipFrame::swapped(fv2, part, &frame, u_range, t_range));
         } else {
-            if u_diff == 0.0 || u_diff >= fat_line_epsilon {
-                calls = bezier_intersections(
-                    v2, &v1, c2, c1, i1, i2, locations, !flip, recursion, calls, u_min, u_max,
-                    t_min_new, t_max_new,
-                );
-            } else {
-                calls = bezier_intersections(
-                    &v1, v2, c1, c2, i1, i2, locations, flip, recursion, calls, t_min_new,
-                    t_max_new, u_min, u_max,
-                );
-            }
+            tracer.step(&step.decided(ClipDecision::Repeat));
+            stack.push(ClipFrame {
+                v1: part,
+                t_min: t_min_new,
+                t_max: t_max_new,
+                ..frame
+            });
         }
     }
 
-    return calls;
+    (calls, max_recursion)
 }
 
-/// 寻找最大值，支持浮点数运算
-fn find_max<I>(iterable: I) -> I::Item
-where
-    I: IntoIterator,
-    I::Item: PartialOrd,
-{
-    iterable
-        .into_iter()
-        .fold(None, |max, x| match max {
-            None => Some(x),
-            Some(y) => Some(if x > y { x } else { y }),
-        })
-        .unwrap()
-}
-fn find_min<I>(iterable: I) -> I::Item
-where
-    I: IntoIterator,
-    I::Item: PartialOrd,
-{
-    iterable
-        .into_iter()
-        .fold(None, |min, x| match min {
-            None => Some(x),
-            Some(y) => Some(if x < y { x } else { y }),
-        })
-        .unwrap()
+/// 控制点的包围盒 `[min_x, min_y, max_x, max_y]`
+fn curve_bounds<F: Float>(v: &Curve<F>) -> [F; 4] {
+    [
+        v[0].min(v[2]).min(v[4]).min(v[6]),
+        v[1].min(v[3]).min(v[5]).min(v[7]),
+        v[0].max(v[2]).max(v[4]).max(v[6]),
+        v[1].max(v[3]).max(v[5]).max(v[7]),
+    ]
 }
 
-fn is_point_on_segment(px: f64, py: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> bool {
+fn is_point_on_segment<F: Float>(px: F, py: F, x1: F, y1: F, x2: F, y2: F) -> bool {
     let within_bounds =
         (x1.min(x2) <= px && px <= x1.max(x2)) && (y1.min(y2) <= py && py <= y1.max(y2));
     if !within_bounds {
@@ -643,35 +859,202 @@ fn is_point_on_segment(px: f64, py: f64, x1: f64, y1: f64, x2: f64, y2: f64) ->
     }
 
     let cross_product = (py - y1) * (x2 - x1) - (px - x1) * (y2 - y1);
-    cross_product.abs() < EPSILON
+    cross_product.abs() < F::EPSILON
 }
 
-fn get_curve_intersections(
-    v1: &[f64; 8],
-    v2: &[f64; 8],
-    i1: f64,
-    i2: f64,
-    locations: &mut Vec<[f64; 8]>,
-) {
-    let epsilon = EPSILON; // EPSILON should be defined somewhere in the scope
-    let v1_min_x = find_min(vec![v1[0], v1[2], v1[4], v1[6]]);
-    let v1_max_x = find_max(vec![v1[0], v1[2], v1[4], v1[6]]);
-    let v1_min_y = find_min(vec![v1[1], v1[3], v1[5], v1[7]]);
-    let v1_max_y = find_max(vec![v1[1], v1[3], v1[5], v1[7]]);
-    let v2_min_x = find_min(vec![v2[0], v2[2], v2[4], v2[6]]);
-    let v2_max_x = find_max(vec![v2[0], v2[2], v2[4], v2[6]]);
-    let v2_min_y = find_min(vec![v2[1], v2[3], v2[5], v2[7]]);
-    let v2_max_y = find_max(vec![v2[1], v2[3], v2[5], v2[7]]);
+/// 按坐标字典序比较两条曲线
+fn compare_curves<F: Float>(v1: &Curve<F>, v2: &Curve<F>) -> Ordering {
+    v1.iter()
+        .zip(v2)
+        .map(|(a, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .find(|&order| order != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// 交换相交点中两条曲线的字段
+fn swap_location<F: Float>(location: &[F; 8]) -> [F; 8] {
+    let [t1, i1, x1, y1, t2, i2, x2, y2] = *location;
+    [t2, i2, x2, y2, t1, i1, x1, y1]
+}
+
+/// 端点接触的处理方式
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EndpointPolicy {
+    /// 保留所有端点接触，包括相邻曲线的连接点
+    IncludeAll,
+    /// 排除同时位于两条曲线端点上的接触
+    #[default]
+    ExcludeShared,
+    /// 排除位于任一曲线端点上的接触，只保留内部相交
+    ExcludeAll,
+}
+
+impl EndpointPolicy {
+    /// 判断相交点是否保留
+    pub fn keeps<F: Float>(self, location: &[F; 8]) -> bool {
+        let endpoint1 = is_endpoint(location[0]);
+        let endpoint2 = is_endpoint(location[4]);
+        match self {
+            EndpointPolicy::IncludeAll => true,
+            EndpointPolicy::ExcludeShared => !(endpoint1 && endpoint2),
+            EndpointPolicy::ExcludeAll => !(endpoint1 || endpoint2),
+        }
+    }
+}
+
+/// 参数是否位于曲线端点
+fn is_endpoint<F: Float>(t: F) -> bool {
+    is_curve_zero(t) || is_curve_zero(F::ONE - t)
+}
+
+/// 两点是否在几何容差内重合
+fn is_close<F: Float>(x1: F, y1: F, x2: F, y2: F) -> bool {
+    let (dx, dy) = (x1 - x2, y1 - y2);
+    dx * dx + dy * dy <= F::GEOMETRIC_EPSILON * F::GEOMETRIC_EPSILON
+}
+
+/// 控制点与起点/终点重合的直线
+fn is_line<F: Float>(v: &Curve<F>) -> bool {
+    v[2] == v[0] && v[3] == v[1] && v[4] == v[6] && v[5] == v[7]
+}
+
+/// 在容差内判断曲线是否为直线，手柄需要位于弦上且不超出弦的范围
+fn is_straight<F: Float>(v: &Curve<F>) -> bool {
+    let (h1x, h1y) = (v[2] - v[0], v[3] - v[1]);
+    let (h2x, h2y) = (v[4] - v[6], v[5] - v[7]);
+    if is_zero(h1x) && is_zero(h1y) && is_zero(h2x) && is_zero(h2y) {
+        return true;
+    }
+    let (vx, vy) = (v[6] - v[0], v[7] - v[1]);
+    let div = vx * vx + vy * vy;
+    if is_zero(div) {
+        return false;
+    }
+    let len = div.sqrt();
+    let epsilon = F::GEOMETRIC_EPSILON;
+    if (vx * h1y - vy * h1x).abs() / len >= epsilon || (vx * h2y - vy * h2x).abs() / len >= epsilon
+    {
+        return false;
+    }
+    let s1 = (vx * h1x + vy * h1y) / div;
+    let s2 = (vx * h2x + vy * h2y) / div;
+    s1 >= F::ZERO && s1 <= F::ONE && s2 <= F::ZERO && s2 >= -F::ONE
+}
+
+/// 直线分支得到的是弦上的参数，按曲线的参数化转换为曲线参数
+///
+/// 控制点与端点重合的直线沿用弦上的参数
+fn chord_to_curve_time<F: Float>(v: &Curve<F>, s: F) -> F {
+    if is_line(v) || s <= F::ZERO || s >= F::ONE {
+        return s;
+    }
+    let (vx, vy) = (v[6] - v[0], v[7] - v[1]);
+    let div = vx * vx + vy * vy;
+    let s1 = (vx * (v[2] - v[0]) + vy * (v[3] - v[1])) / div;
+    let s2 = (vx * (v[4] - v[0]) + vy * (v[5] - v[1])) / div;
+    let mut p = bezier_coeffs(F::ZERO, s1, s2, F::ONE);
+    // 手柄均匀分布时高次项只剩舍入误差，直接置零避免退化
+    for coeff in p.iter_mut().take(2) {
+        if is_zero(*coeff) {
+            *coeff = F::ZERO;
+        }
+    }
+    p[3] -= s;
+    let t = cubic_roots(&p)[0];
+    if t < F::ZERO {
+        return s;
+    }
+    // 牛顿迭代修正求根误差
+    let three = F::from_f64(3.0);
+    let two = F::from_f64(2.0);
+    let mut t = t;
+    for _ in 0..2 {
+        let f = ((p[0] * t + p[1]) * t + p[2]) * t + p[3];
+        let df = (three * p[0] * t + two * p[1]) * t + p[2];
+        if df == F::ZERO {
+            break;
+        }
+        t = (t - f / df).clamp(F::ZERO, F::ONE);
+    }
+    t
+}
+
+/// 计算两条曲线的相交点
+///
+/// 裁剪顺序会影响结果，因此总是以坐标字典序较小的曲线作为第一条曲线计算，
+/// 保证交换两条曲线时结果只是字段互换。端点接触统一按 `policy` 过滤
+fn get_curve_intersections<F: Float, T: ClipTracer<F>>(
+    v1: &Curve<F>,
+    v2: &Curve<F>,
+    i1: F,
+    i2: F,
+    policy: EndpointPolicy,
+    locations: &mut Vec<[F; 8]>,
+    tracer: &mut T,
+) -> CurveBranch {
+    let start = locations.len();
+    let (straight1, straight2) = (is_straight(v1), is_straight(v2));
+    let swap = compare_curves(v1, v2) == Ordering::Greater;
+    let branch = if swap {
+        get_ordered_curve_intersections(v2, v1, i2, i1, straight2, straight1, locations, tracer)
+    } else {
+        get_ordered_curve_intersections(v1, v2, i1, i2, straight1, straight2, locations, tracer)
+    };
+    for location in &mut locations[start..] {
+        if swap {
+            *location = swap_location(location);
+        }
+        if straight1 {
+            location[0] = chord_to_curve_time(v1, location[0]);
+        }
+        if straight2 {
+            location[4] = chord_to_curve_time(v2, location[4]);
+        }
+    }
+    if policy != EndpointPolicy::IncludeAll {
+        let mut kept = start;
+        for k in start..locations.len() {
+            if policy.keeps(&locations[k]) {
+                locations[kept] = locations[k];
+                kept += 1;
+            }
+        }
+        locations.truncate(kept);
+    }
+    branch
+}
+
+fn get_ordered_curve_intersections<F: Float, T: ClipTracer<F>>(
+    v1: &Curve<F>,
+    v2: &Curve<F>,
+    i1: F,
+    i2: F,
+    straight1: bool,
+    straight2: bool,
+    locations: &mut Vec<[F; 8]>,
+    tracer: &mut T,
+) -> CurveBranch {
+    let epsilon = F::EPSILON;
+    let zero = F::ZERO;
+    let one = F::ONE;
+    let [v1_min_x, v1_min_y, v1_max_x, v1_max_y] = curve_bounds(v1);
+    let [v2_min_x, v2_min_y, v2_max_x, v2_max_y] = curve_bounds(v2);
 
     if v1_max_x + epsilon > v2_min_x
         && v1_min_x - epsilon < v2_max_x
         && v1_max_y + epsilon > v2_min_y
         && v1_min_y - epsilon < v2_max_y
     {
-        let straight1 = v1[2] == v1[0] && v1[3] == v1[1] && v1[4] == v1[6] && v1[5] == v1[7];
-        let straight2 = v2[2] == v2[0] && v2[3] == v2[1] && v2[4] == v2[6] && v2[5] == v2[7];
+        // 端点重合统一在这里记录，各分支不再返回两端都在端点上的相交点
+        for (t1, x1, y1) in [(zero, v1[0], v1[1]), (one, v1[6], v1[7])] {
+            for (t2, x2, y2) in [(zero, v2[0], v2[1]), (one, v2[6], v2[7])] {
+                if is_close(x1, y1, x2, y2) {
+                    locations.push([t1, i1, x1, y1, t2, i2, x2, y2]);
+                }
+            }
+        }
         let straight = straight1 && straight2;
-        let flip = straight1 && !straight2;
         // 过滤共线情况
         if v1[0] == v2[6]
             && v1[1] == v2[7]
@@ -680,9 +1063,9 @@ fn get_curve_intersections(
             && v1[4] == v2[2]
             && v1[5] == v2[3]
         {
-            return;
+            return CurveBranch::Skipped;
         }
-        // 直线相交，控制点和起点/终点一致
+        // 直线相交，手柄在弦上
         if straight {
             let pt = line_intersection(v1[0], v1[1], v1[6], v1[7], v2[0], v2[1], v2[6], v2[7]);
             match pt {
@@ -701,11 +1084,7 @@ fn get_curve_intersections(
                                 calculate_t_value(v1[0], v1[1], v1[6], v1[7], data[0], data[1]);
                             let t2 =
                                 calculate_t_value(v2[0], v2[1], v2[6], v2[7], data[0], data[1]);
-                            if (t1 == 0.0 && t2 == 1.0)
-                                || (t1 == 1.0 && t2 == 0.0)
-                                || (t1 == 0.0 && t2 == 0.0)
-                                || (t1 == 1.0 && t2 == 1.0)
-                            {
+                            if is_endpoint(t1) && is_endpoint(t2) {
                                 continue;
                             }
                             locations.push([t1, i1, data[0], data[1], t2, i2, data[0], data[1]]);
@@ -713,38 +1092,22 @@ fn get_curve_intersections(
                     }
                 }
                 Some((x, y)) => {
-                    let mut count = 0;
-                    if (x == v1[0] && y == v1[1]) || (x == v1[6] && y == v1[7]) {
-                        count += 1;
-                    }
-                    if (x == v2[0] && y == v2[1]) || (x == v2[6] && y == v2[7]) {
-                        count += 1;
-                    }
-                    // 过滤起点和终点重合情况
-                    if count == 2 {
-                        return;
-                    }
                     let t1 = calculate_t_value(v1[0], v1[1], v1[6], v1[7], x, y);
                     let t2 = calculate_t_value(v2[0], v2[1], v2[6], v2[7], x, y);
-                    if t1 > 1.0 || t1 < 0.0 {
-                        return;
+                    if t1 > one || t1 < zero {
+                        return CurveBranch::LineLine;
                     }
-                    if t2 > 1.0 || t2 < 0.0 {
-                        return;
+                    if t2 > one || t2 < zero {
+                        return CurveBranch::LineLine;
                     }
-                    if t1 > 1.0 - GEOMETRIC_EPSILON || t1 < GEOMETRIC_EPSILON {
-                        count += 1;
-                    }
-                    if t2 > 1.0 - GEOMETRIC_EPSILON || t2 < GEOMETRIC_EPSILON {
-                        count += 1;
-                    }
-                    if count == 4 {
-                        return;
+                    // 过滤起点和终点重合情况
+                    if is_endpoint(t1) && is_endpoint(t2) {
+                        return CurveBranch::LineLine;
                     }
-                    locations.push([t1, i1 as f64, x, y, t2, i2 as f64, x, y]);
+                    locations.push([t1, i1, x, y, t2, i2, x, y]);
                 }
             }
-            return;
+            return CurveBranch::LineLine;
         }
         // 直线和曲线相交
         if straight1 || straight2 {
@@ -759,231 +1122,223 @@ fn get_curve_intersections(
                 [curve[0], curve[1], line[0], line[1], line[2], line[3]],
                 [curve[6], curve[7], line[0], line[1], line[2], line[3]],
             ];
-            let mut count = 0;
+            let mut on_line = [false; 2];
             for (i, data) in test_point.iter().enumerate() {
                 if is_point_on_segment(data[0], data[1], data[2], data[3], data[4], data[5]) {
+                    on_line[i] = true;
                     let t = calculate_t_value(line[0], line[1], line[2], line[3], data[0], data[1]);
-                    if t == 0.0 || t == 1.0 {
+                    if is_endpoint(t) {
                         continue;
                     }
-                    count += 1;
+                    let curve_t = if i == 0 { zero } else { one };
                     if straight1 {
-                        locations.push([t, i1, data[0], data[1], i as f64, i2, data[0], data[1]]);
+                        locations.push([t, i1, data[0], data[1], curve_t, i2, data[0], data[1]]);
                     } else {
-                        locations.push([i as f64, i1, data[0], data[1], t, i2, data[0], data[1]]);
+                        locations.push([curve_t, i1, data[0], data[1], t, i2, data[0], data[1]]);
                     }
                 }
             }
-            // 如果都存在的话，不需要进行下面计算
-            if count == 2 {
-                return;
-            }
             let instersections = line_and_curve_intersection(curve, &line);
             for item in &instersections {
                 // 排除端点重合
-                if (item.3 > 1.0 - GEOMETRIC_EPSILON || item.3 < GEOMETRIC_EPSILON)
-                    && (item.0 > 1.0 - GEOMETRIC_EPSILON || item.0 < GEOMETRIC_EPSILON)
+                if is_endpoint(item.3) && is_endpoint(item.0) {
+                    continue;
+                }
+                // 排除上面已记录的曲线端点在直线上case
+                if (is_curve_zero(item.0) && on_line[0])
+                    || (is_curve_zero(one - item.0) && on_line[1])
                 {
                     continue;
                 }
                 if straight1 {
-                    // 排除曲线端点在直线上case
-                    if item.0 == 1.0 || item.1 == 0.0 {
-                        return;
-                    }
                     locations.push([item.3, i1, item.4, item.5, item.0, i2, item.1, item.2]);
                 } else {
-                    // 排除曲线端点在直线上case
-                    if item.3 == 1.0 || item.3 == 0.0 {
-                        return;
-                    }
                     locations.push([item.0, i1, item.1, item.2, item.3, i2, item.4, item.5]);
                 }
             }
-            return;
+            return CurveBranch::LineCurve;
         }
-        let vv1 = if flip { v2 } else { v1 };
-        let vv2 = if flip { v1 } else { v2 };
-
-        bezier_intersections(
-            vv1, vv2, vv1, vv2, i1, i2, locations, flip, 0, 0, 0.0, 1.0, 0.0, 1.0,
-        );
+        let (calls, recursion) = bezier_intersections(v1, v2, i1, i2, locations, tracer);
+        return CurveBranch::CurveCurve { calls, recursion };
     }
+    CurveBranch::Skipped
 }
 
-fn get_self_intersection(v: &[f64; 8]) -> Option<Vec<f64>> {
-    let (x0, y0, x1, y1, x2, y2, x3, y3) = (v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]);
-    if x0 == x1 && y0 == y1 && x2 == x3 && y2 == y3 {
-        return None;
-    }
-
-    let a1 = x0 * (y3 - y2) + y0 * (x2 - x3) + x3 * y2 - y3 * x2;
-    let a2 = x1 * (y0 - y3) + y1 * (x3 - x0) + x0 * y3 - y0 * x3;
-    let a3 = x2 * (y1 - y0) + y2 * (x0 - x1) + x1 * y0 - y1 * x0;
-
-    let mut d3 = 3.0 * a3;
-    let mut d2 = d3 - a2;
-    let mut d1 = d2 - a2 + a1;
-
-    let l = (d1 * d1 + d2 * d2 + d3 * d3).sqrt();
-    let s = if l != 0.0 { 1.0 / l } else { 0.0 };
-
-    d1 *= s;
-    d2 *= s;
-    d3 *= s;
-
-    if is_zero(d1) {
-        return None;
-    }
-
-    let d = 3.0 * d2 * d2 - 4.0 * d1 * d3;
-
-    if d >= 0.0 {
-        return None;
-    }
-
-    let f1 = if d > 0.0 {
-        (d / 3.0).sqrt()
-    } else {
-        (-d).sqrt()
-    };
-    let f2 = 2.0 * d1;
-    let t1 = (d2 + f1) / f2;
-    let t2 = (d2 - f1) / f2;
-
-    let has_roots = true;
-    let t1_ok = has_roots && t1 > 0.0 && t1 < 1.0;
-    let t2_ok = has_roots && t2 > 0.0 && t2 < 1.0;
-
-    if !(t1_ok && t2_ok) {
-        return None;
-    }
-
-    Some(if t1_ok || t2_ok {
-        if t1_ok && t2_ok {
-            if t1 < t2 {
-                vec![t1, t2]
-            } else {
-                vec![t2, t1]
-            }
-        } else {
-            vec![if t1_ok { t1 } else { t2 }]
-        }
-    } else {
-        vec![]
-    })
+/// 曲线序号转换为浮点数
+fn index<F: Float>(i: usize) -> F {
+    F::from_f64(i as f64)
 }
 
-pub fn get_intersections(
-    curves1: &Vec<[f64; 8]>,
-    curves2: &Vec<[f64; 8]>,
+/// 求两组曲线的相交点，追加到 `locations` 中
+///
+/// 结果与曲线顺序无关，新追加的部分按 `sort_locations` 规范排序。
+/// `is_self` 时还包括每条曲线自身的环。尖点不是两段曲线相交，不会报告，需要时用 `get_self_intersection` 分类
+pub fn get_intersections<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
     is_self: bool,
-    locations: &mut Vec<[f64; 8]>,
+    locations: &mut Vec<[F; 8]>,
 ) {
-    let bounds_collisions = CollisionDetection::find_curve_bounds_collisions(
-        &curves1,
-        &curves2,
+    get_intersections_with_policy(
+        curves1,
+        curves2,
         is_self,
-        GEOMETRIC_EPSILON,
+        EndpointPolicy::default(),
+        locations,
     );
-    for i in 0..curves1.len() {
-        let curve1 = curves1[i];
-        if is_self {
-            match get_self_intersection(&curve1) {
-                Some(t) => {
-                    if !t[0].is_nan() && !t[1].is_nan() {
-                        if let Some([x1, y1]) = evaluate(&curve1, t[0], 0) {
-                            if let Some([x2, y2]) = evaluate(&curve1, t[1], 0) {
-                                locations.push([t[0], i as f64, x1, y1, t[1], i as f64, x2, y2]);
+}
+
+/// 按指定的端点接触处理方式求交
+pub fn get_intersections_with_policy<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    is_self: bool,
+    policy: EndpointPolicy,
+    locations: &mut Vec<[F; 8]>,
+) {
+    get_intersections_with_stats(curves1, curves2, is_self, policy, locations);
+}
+
+/// 求交并返回统计数据
+pub fn get_intersections_with_stats<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    is_self: bool,
+    policy: EndpointPolicy,
+    locations: &mut Vec<[F; 8]>,
+) -> Stats {
+    let mut job = IntersectionJob::new(curves1, curves2, is_self, policy);
+    job.resume(curves1, curves2, &mut (), locations);
+    job.stats()
+}
+
+/// 从 `cursor` 开始求交，预算用完时暂停并返回下一步的位置，全部完成时返回 None
+///
+/// 每条曲线的候选曲线从 `bvh` 查询后放在 `candidates` 中，按序号排列，自交时只保留序号更大的曲线
+pub(crate) fn intersect_from<F: Float, B: Budget>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    is_self: bool,
+    policy: EndpointPolicy,
+    bvh: &Bvh<F>,
+    candidates: &mut Vec<u32>,
+    cursor: IntersectionCursor,
+    budget: &mut B,
+    stats: &mut Stats,
+    locations: &mut Vec<[F; 8]>,
+) -> Option<IntersectionCursor> {
+    let start = locations.len();
+    let mut next = None;
+    'curves: for (i, &curve1) in curves1.iter().enumerate().skip(cursor.curve as usize) {
+        candidates.clear();
+        let bounds = CollisionDetection::get_curve_bounds(&curve1);
+        bvh.query(bounds, F::GEOMETRIC_EPSILON, |j| {
+            if !is_self || j as usize > i {
+                candidates.push(j);
+            }
+        });
+        candidates.sort_unstable();
+        let first = if i == cursor.curve as usize {
+            cursor.step as usize
+        } else {
+            0
+        };
+        for step in first..=candidates.len() {
+            let mut work = 1;
+            if step == 0 {
+                // 不是自交时这一步只有候选查询
+                if is_self {
+                    if let SelfIntersection::Loop { t1, t2 }
+                    | SelfIntersection::EndpointLoop { t1, t2 } = get_self_intersection(&curve1)
+                    {
+                        if let (Some([x1, y1]), Some([x2, y2])) =
+                            (evaluate(&curve1, t1, 0), evaluate(&curve1, t2, 0))
+                        {
+                            // 端点处闭合的环与相邻曲线的连接点一样受端点策略约束
+                            let location = [t1, index(i), x1, y1, t2, index(i), x2, y2];
+                            if policy.keeps(&location) {
+                                locations.push(location);
                             }
                         }
                     }
                 }
-                None => (),
+            } else {
+                let j = candidates[step - 1] as usize;
+                let curve2 = curves2[j];
+                let count = locations.len();
+                let branch = get_curve_intersections(
+                    &curve1,
+                    &curve2,
+                    index(i),
+                    index(j),
+                    policy,
+                    locations,
+                    &mut (),
+                );
+                stats.record(branch, locations.len() > count);
+                if let CurveBranch::CurveCurve { calls, .. } = branch {
+                    work += calls as u32;
+                }
             }
-        }
-        for &index in &bounds_collisions[i] {
-            if !is_self || index > i as i32 {
-                let curve2 = curves2[index as usize];
-                get_curve_intersections(&curve1, &curve2, i as f64, index as f64, locations);
+            if budget.spend(work) {
+                next = if step < candidates.len() {
+                    Some(IntersectionCursor {
+                        curve: i as u32,
+                        step: step as u32 + 1,
+                    })
+                } else if i + 1 < curves1.len() {
+                    Some(IntersectionCursor {
+                        curve: i as u32 + 1,
+                        step: 0,
+                    })
+                } else {
+                    None
+                };
+                break 'curves;
             }
         }
     }
+    sort_locations(&mut locations[start..]);
+    next
 }
 
-// /// 寻找两条路径的相交点
-// #[wasm_bindgen]
-// pub fn rust_get_muti_intersections(curves1: JsValue, curves2: JsValue) -> Result<JsValue, JsValue> {
-//     let mut locations = vec![];
-//     let curves1_vec = serde_wasm_bindgen::from_value(curves1)?;
-//     let curves2_vec = serde_wasm_bindgen::from_value(curves2)?;
-
-//     get_intersections(&curves1_vec, &curves2_vec, false, &mut locations);
-
-//     Ok(serde_wasm_bindgen::to_value(&locations)?)
-// }
-
-// /// 寻找当前路径的相交点
-// #[wasm_bindgen]
-// pub fn rust_get_intersections(curves: JsValue) -> Result<JsValue, JsValue> {
-//     let mut locations = vec![];
-//     let curves_vec = serde_wasm_bindgen::from_value(curves)?;
-
-//     get_intersections(&curves_vec, &curves_vec, true, &mut locations);
-
-//     Ok(serde_wasm_bindgen::to_value(&locations)?)
-// }
-
-/// 寻找两条路径的相交点（f64类型）
-#[wasm_bindgen]
-pub fn rust_get_muti_intersections(slice1: &[f64], slice2: &[f64]) -> Vec<f64> {
-    let mut locations = vec![];
-    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
-    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
-    get_intersections(&curves1, &curves2, false, &mut locations);
-    flatten(locations)
-}
-
-/// 寻找当前路径的相交点（f64类型）
-#[wasm_bindgen]
-pub fn rust_get_intersections(slice: &[f64]) -> Vec<f64> {
+/// 求两条曲线的相交点，贝塞尔裁剪的每一步交给 `tracer`
+///
+/// 结果与 `get_intersections(&[*v1], &[*v2], false, ..)` 相同。
+/// 裁剪在规范顺序下进行，`ClipStep::flip` 为 false 时 `v1` 片段属于坐标字典序较小的曲线。
+/// 按直线处理的曲线不经过裁剪，不会产生步骤
+pub fn get_curve_intersections_traced<F: Float, T: ClipTracer<F>>(
+    v1: &Curve<F>,
+    v2: &Curve<F>,
+    policy: EndpointPolicy,
+    tracer: &mut T,
+) -> Vec<[F; 8]> {
     let mut locations = vec![];
-    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
-    get_intersections(&curves, &curves, true, &mut locations);
-    flatten(locations)
-}
-
-/// 切割部分曲线[t1,t2]
-#[wasm_bindgen]
-pub fn rust_split_cubic_bezier_part(v: &[f64], t1: f64, t2: f64) -> Vec<f64> {
-    let mut v_part = [0.0; 8];
-    for (i, &value) in v.iter().enumerate() {
-        v_part[i] = value;
-    }
-    if t1 > 0.0 {
-        v_part = split_cubic_bezier(&v_part, t1).1;
-    }
-    if t2 < 1.0 {
-        let t = (t2 - t1) / (1.0 - t1);
-        v_part = split_cubic_bezier(&v_part, t).0;
-    }
-    v_part.to_vec()
+    get_curve_intersections(v1, v2, F::ZERO, F::ZERO, policy, &mut locations, tracer);
+    sort_locations(&mut locations);
+    locations
 }
 
-fn flatten(vec_of_arrays: Vec<[f64; 8]>) -> Vec<f64> {
-    let mut flattened_vec = Vec::with_capacity(vec_of_arrays.len() * 8);
-    for array in vec_of_arrays {
-        flattened_vec.extend_from_slice(&array);
-    }
-    flattened_vec
+/// 相交点的规范排序：依次按 `i1`、`i2`、`t1`、`t2` 升序
+pub fn sort_locations<F: Float>(locations: &mut [[F; 8]]) {
+    locations.sort_by(|a, b| {
+        [1, 5, 0, 4]
+            .iter()
+            .map(|&k| a[k].partial_cmp(&b[k]).unwrap_or(Ordering::Equal))
+            .find(|&order| order != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
 }
 
-fn to_vec_of_arrays(slice: &[f64]) -> Vec<[f64; 8]> {
-    return slice
-        .chunks_exact(8)
-        .map(|chunk| {
-            let array: [f64; 8] = chunk.try_into().unwrap();
-            array
-        })
-        .collect();
+/// 求交并对结果做牛顿迭代修正，`accuracy` 为期望的残差距离
+pub fn get_refined_intersections<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    is_self: bool,
+    policy: EndpointPolicy,
+    accuracy: F,
+) -> Vec<RefinedLocation<F>> {
+    let mut locations = vec![];
+    get_intersections_with_policy(curves1, curves2, is_self, policy, &mut locations);
+    refine_locations(curves1, curves2, is_self, &locations, accuracy)
 }
@@ -0,0 +1,213 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm-serde")]
+use tsify::Tsify;
+
+use alloc::vec::Vec;
+
+use crate::{evaluate, get_crossing, is_endpoint, Crossing, Curve, Float};
+
+/// 相交点类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "wasm-serde", derive(Tsify))]
+pub enum LocationKind {
+    /// 两条曲线内部的相交
+    Interior,
+    /// 位于其中一条曲线的端点上
+    Endpoint,
+    /// 同时位于两条曲线的端点上
+    SharedEndpoint,
+    /// 曲线自身的自交点
+    SelfIntersection,
+}
+
+/// 结构化的相交点，`point` 为 `[x, y]`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "wasm-serde", derive(Tsify))]
+pub struct Intersection {
+    pub curve1: u32,
+    pub t1: f64,
+    pub point1: [f64; 2],
+    pub curve2: u32,
+    pub t2: f64,
+    pub point2: [f64; 2],
+    pub kind: LocationKind,
+    /// 两条曲线在相交点处的切线、法线和曲率，需要时通过 `with_geometry` 计算
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[cfg_attr(feature = "wasm-serde", tsify(optional))]
+    pub geometry: Option<LocationGeometry>,
+}
+
+/// 相交点处两条曲线的局部几何信息
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(feature = "wasm-serde", derive(Tsify))]
+pub struct LocationGeometry {
+    /// 第一条曲线的单位切线
+    pub tangent1: [f64; 2],
+    /// 第一条曲线的单位法线，由切线顺时针旋转 90° 得到，与 `evaluate` 的 type = 2 一致
+    pub normal1: [f64; 2],
+    /// 第一条曲线的有向曲率
+    pub curvature1: f64,
+    pub tangent2: [f64; 2],
+    pub normal2: [f64; 2],
+    pub curvature2: f64,
+    /// 从 `tangent1` 转到 `tangent2` 的有向角，单位为弧度，范围 (-π, π]
+    pub angle: f64,
+    /// 第二条曲线穿过第一条曲线的方向
+    pub crossing: Crossing,
+}
+
+/// 单位切线，端点处手柄长度为零时取两个手柄之间的方向，与 paper.js 一致
+pub(crate) fn unit_tangent<F: Float>(v: &Curve<F>, t: F) -> [F; 2] {
+    let Some([mut x, mut y]) = evaluate(v, t, 1) else {
+        return [F::ZERO; 2];
+    };
+    if x == F::ZERO && y == F::ZERO {
+        x = v[4] - v[2];
+        y = v[5] - v[3];
+    }
+    let len = (x * x + y * y).sqrt();
+    if len == F::ZERO {
+        [F::ZERO; 2]
+    } else {
+        [x / len, y / len]
+    }
+}
+
+impl LocationGeometry {
+    /// 计算 `v1` 上 `t1` 处与 `v2` 上 `t2` 处的几何信息，切线无法确定时为零向量
+    pub fn new<F: Float>(v1: &Curve<F>, t1: F, v2: &Curve<F>, t2: F) -> Self {
+        let [tx1, ty1] = unit_tangent(v1, t1);
+        let [tx2, ty2] = unit_tangent(v2, t2);
+        let curvature = |v, t| evaluate(v, t, 3).map_or(F::ZERO, |k| k[0]).to_f64();
+        LocationGeometry {
+            tangent1: [tx1.to_f64(), ty1.to_f64()],
+            normal1: [ty1.to_f64(), -tx1.to_f64()],
+            curvature1: curvature(v1, t1),
+            tangent2: [tx2.to_f64(), ty2.to_f64()],
+            normal2: [ty2.to_f64(), -tx2.to_f64()],
+            curvature2: curvature(v2, t2),
+            angle: (tx1 * ty2 - ty1 * tx2)
+                .atan2(tx1 * tx2 + ty1 * ty2)
+                .to_f64(),
+            crossing: get_crossing(v1, t1, v2, t2),
+        }
+    }
+
+    /// 由 `get_intersections` 的结果计算，`curves1` 和 `curves2` 为求交时传入的曲线
+    pub fn from_location<F: Float>(
+        curves1: &[Curve<F>],
+        curves2: &[Curve<F>],
+        location: &[F; 8],
+    ) -> Self {
+        let i1 = location[1].to_f64() as usize;
+        let i2 = location[5].to_f64() as usize;
+        LocationGeometry::new(&curves1[i1], location[0], &curves2[i2], location[4])
+    }
+
+    /// 按 `[tx1, ty1, nx1, ny1, k1, tx2, ty2, nx2, ny2, k2, angle, crossing]` 展开，
+    /// `crossing` 为 `Crossing::sign`
+    pub fn to_array(&self) -> [f64; 12] {
+        [
+            self.tangent1[0],
+            self.tangent1[1],
+            self.normal1[0],
+            self.normal1[1],
+            self.curvature1,
+            self.tangent2[0],
+            self.tangent2[1],
+            self.normal2[0],
+            self.normal2[1],
+            self.curvature2,
+            self.angle,
+            self.crossing.sign() as f64,
+        ]
+    }
+}
+
+/// 计算每个相交点的几何信息
+pub fn location_geometries<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    locations: &[[F; 8]],
+) -> Vec<LocationGeometry> {
+    locations
+        .iter()
+        .map(|location| LocationGeometry::from_location(curves1, curves2, location))
+        .collect()
+}
+
+impl Intersection {
+    /// 由 `get_intersections` 的结果构造，`is_self` 时序号相同表示曲线自交
+    pub fn from_location<F: Float>(location: &[F; 8], is_self: bool) -> Self {
+        let [t1, i1, x1, y1, t2, i2, x2, y2] = location.map(|v| v.to_f64());
+        let kind = if is_self && i1 == i2 {
+            LocationKind::SelfIntersection
+        } else {
+            match (is_endpoint(location[0]), is_endpoint(location[4])) {
+                (true, true) => LocationKind::SharedEndpoint,
+                (false, false) => LocationKind::Interior,
+                _ => LocationKind::Endpoint,
+            }
+        };
+        Intersection {
+            curve1: i1 as u32,
+            t1,
+            point1: [x1, y1],
+            curve2: i2 as u32,
+            t2,
+            point2: [x2, y2],
+            kind,
+            geometry: None,
+        }
+    }
+
+    /// 附加几何信息，`curves1` 和 `curves2` 为求交时传入的曲线
+    pub fn with_geometry<F: Float>(mut self, curves1: &[Curve<F>], curves2: &[Curve<F>]) -> Self {
+        let (v1, v2) = (
+            &curves1[self.curve1 as usize],
+            &curves2[self.curve2 as usize],
+        );
+        self.geometry = Some(LocationGeometry::new(
+            v1,
+            F::from_f64(self.t1),
+            v2,
+            F::from_f64(self.t2),
+        ));
+        self
+    }
+}
+
+/// 计算每个相交点处第二条曲线穿过第一条曲线的方向
+pub fn location_crossings<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    locations: &[[F; 8]],
+) -> Vec<Crossing> {
+    locations
+        .iter()
+        .map(|location| {
+            let i1 = location[1].to_f64() as usize;
+            let i2 = location[5].to_f64() as usize;
+            get_crossing(&curves1[i1], location[0], &curves2[i2], location[4])
+        })
+        .collect()
+}
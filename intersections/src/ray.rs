@@ -0,0 +1,237 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::collision_detection::CollisionDetection;
+use crate::{evaluate, is_close, line_and_curve_roots, signed_distance, Bvh, Curve, Float};
+
+/// 射线或直线与曲线的交点，`distance` 为交点沿方向到起点的有符号距离
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit<F = f64> {
+    pub curve: u32,
+    pub t: F,
+    pub point: [F; 2],
+    pub distance: F,
+}
+
+impl<F: Float> RayHit<F> {
+    /// 按 `[t, i, x, y, distance]` 展开，前 4 项与相交点的前半部分格式相同
+    pub fn to_array(&self) -> [F; 5] {
+        [
+            self.t,
+            F::from_f64(self.curve as f64),
+            self.point[0],
+            self.point[1],
+            self.distance,
+        ]
+    }
+}
+
+/// 从 `origin` 出发沿单位向量 `unit` 的直线，只保留距离不小于 `min` 的部分
+struct Ray<F> {
+    origin: [F; 2],
+    unit: [F; 2],
+    min: F,
+}
+
+impl<F: Float> Ray<F> {
+    fn new(origin: [F; 2], direction: [F; 2], min: F) -> Option<Self> {
+        let length = (direction[0] * direction[0] + direction[1] * direction[1]).sqrt();
+        if length == F::ZERO || length.is_nan() || length == F::INFINITY {
+            return None;
+        }
+        Some(Ray {
+            origin,
+            unit: [direction[0] / length, direction[1] / length],
+            min,
+        })
+    }
+
+    /// 进入边界框时的距离，边界框扩大几何容差，不相交时返回 `None`
+    fn enter(&self, bounds: &[F; 4]) -> Option<F> {
+        let (mut lo, mut hi) = (self.min, F::INFINITY);
+        for k in 0..2 {
+            let (min, max) = (
+                bounds[k] - F::GEOMETRIC_EPSILON,
+                bounds[k + 2] + F::GEOMETRIC_EPSILON,
+            );
+            if self.unit[k] == F::ZERO {
+                if self.origin[k] < min || self.origin[k] > max {
+                    return None;
+                }
+                continue;
+            }
+            let s1 = (min - self.origin[k]) / self.unit[k];
+            let s2 = (max - self.origin[k]) / self.unit[k];
+            lo = lo.max(s1.min(s2));
+            hi = hi.min(s1.max(s2));
+        }
+        if lo <= hi {
+            Some(lo)
+        } else {
+            None
+        }
+    }
+
+    fn distance_to(&self, p: [F; 2]) -> F {
+        signed_distance(
+            self.origin[0],
+            self.origin[1],
+            self.unit[0],
+            self.unit[1],
+            p[0],
+            p[1],
+            true,
+        )
+        .abs()
+    }
+
+    /// 第 `curve` 条曲线与直线的交点
+    ///
+    /// 端点在容差内位于直线上时也算交点，避免求根时端点处的根因舍入误差丢失。
+    /// 与直线重合的曲线只返回两个端点
+    fn hits(&self, curve: usize, v: &Curve<F>, hits: &mut Vec<RayHit<F>>) {
+        let on_line = |k: usize| self.distance_to([v[k * 2], v[k * 2 + 1]]) <= F::GEOMETRIC_EPSILON;
+        let mut ts = vec![];
+        if (0..4).all(on_line) {
+            ts.extend([F::ZERO, F::ONE]);
+        } else {
+            let line = [
+                self.origin[0],
+                self.origin[1],
+                self.origin[0] + self.unit[0],
+                self.origin[1] + self.unit[1],
+            ];
+            ts.extend(
+                line_and_curve_roots(v, &line)
+                    .into_iter()
+                    .filter(|&t| t >= F::ZERO),
+            );
+            if on_line(0) {
+                ts.push(F::ZERO);
+            }
+            if on_line(3) {
+                ts.push(F::ONE);
+            }
+        }
+        for t in ts {
+            let Some(point) = evaluate(v, t, 0) else {
+                continue;
+            };
+            let distance = (point[0] - self.origin[0]) * self.unit[0]
+                + (point[1] - self.origin[1]) * self.unit[1];
+            if distance >= self.min - F::GEOMETRIC_EPSILON {
+                hits.push(RayHit {
+                    curve: curve as u32,
+                    t,
+                    point,
+                    distance: distance.max(self.min),
+                });
+            }
+        }
+    }
+
+    /// 用层次包围盒找出边界框与直线相交的曲线，返回曲线序号及进入边界框的距离
+    fn candidates(&self, curves: &[Curve<F>]) -> Vec<(F, usize)> {
+        let bounds = CollisionDetection::get_bounds(curves);
+        let mut candidates = vec![];
+        Bvh::new(&bounds).traverse(
+            |b| self.enter(b).is_some(),
+            |i| {
+                if let Some(enter) = self.enter(&bounds[i as usize]) {
+                    candidates.push((enter, i as usize));
+                }
+            },
+        );
+        candidates
+    }
+
+    /// 所有交点按距离排列，同一条曲线上重合的交点和相邻曲线在连接点处的交点只保留一个
+    fn intersect(&self, curves: &[Curve<F>]) -> Vec<RayHit<F>> {
+        let mut candidates = self.candidates(curves);
+        candidates.sort_by_key(|&(_, i)| i);
+        let mut hits = vec![];
+        for (_, i) in candidates {
+            self.hits(i, &curves[i], &mut hits);
+        }
+        hits.sort_by(compare_hits);
+        // 交点位于曲线端点时返回该端点
+        let endpoint = |hit: &RayHit<F>| {
+            let v = &curves[hit.curve as usize];
+            if hit.t <= F::CURVETIME_EPSILON {
+                Some([v[0], v[1]])
+            } else if hit.t >= F::ONE - F::CURVETIME_EPSILON {
+                Some([v[6], v[7]])
+            } else {
+                None
+            }
+        };
+        hits.dedup_by(|b, a| {
+            is_close(a.point[0], a.point[1], b.point[0], b.point[1])
+                && (a.curve == b.curve
+                    || matches!(
+                        (endpoint(a), endpoint(b)),
+                        (Some(p), Some(q)) if is_close(p[0], p[1], q[0], q[1])
+                    ))
+        });
+        hits
+    }
+}
+
+fn compare_hits<F: Float>(a: &RayHit<F>, b: &RayHit<F>) -> Ordering {
+    a.distance
+        .partial_cmp(&b.distance)
+        .unwrap_or(Ordering::Equal)
+        .then(a.curve.cmp(&b.curve))
+        .then(a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal))
+}
+
+/// 从 `origin` 出发沿 `direction` 的射线与曲线的所有交点，按到起点的距离排列
+///
+/// 先用边界框的层次包围盒排除不相交的曲线。相邻曲线在连接点处的交点只保留一个，
+/// 不同曲线在内部经过同一点时各自保留，`direction` 为零时没有交点
+pub fn intersect_ray<F: Float>(
+    origin: [F; 2],
+    direction: [F; 2],
+    curves: &[Curve<F>],
+) -> Vec<RayHit<F>> {
+    Ray::new(origin, direction, F::ZERO).map_or(vec![], |ray| ray.intersect(curves))
+}
+
+/// 经过 `origin`、方向为 `direction` 的直线与曲线的所有交点，在起点之前的交点距离为负
+pub fn intersect_infinite_line<F: Float>(
+    origin: [F; 2],
+    direction: [F; 2],
+    curves: &[Curve<F>],
+) -> Vec<RayHit<F>> {
+    Ray::new(origin, direction, F::NEG_INFINITY).map_or(vec![], |ray| ray.intersect(curves))
+}
+
+/// 射线遇到的第一个交点，起点在曲线上时距离为 0
+///
+/// 用层次包围盒找出候选曲线，按射线进入边界框的距离依次检查，进入距离超过当前结果后停止
+pub fn first_hit<F: Float>(
+    origin: [F; 2],
+    direction: [F; 2],
+    curves: &[Curve<F>],
+) -> Option<RayHit<F>> {
+    let ray = Ray::new(origin, direction, F::ZERO)?;
+    let mut candidates = ray.candidates(curves);
+    candidates.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mut best: Option<RayHit<F>> = None;
+    let mut hits = vec![];
+    for (enter, i) in candidates {
+        if best.is_some_and(|best| best.distance < enter) {
+            break;
+        }
+        hits.clear();
+        ray.hits(i, &curves[i], &mut hits);
+        for &hit in &hits {
+            if best.is_none_or(|best| compare_hits(&hit, &best) == Ordering::Less) {
+                best = Some(hit);
+            }
+        }
+    }
+    best
+}
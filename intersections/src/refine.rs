@@ -0,0 +1,204 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::{evaluate, Curve, Float};
+
+/// 牛顿迭代的最大次数
+const MAX_ITERATIONS: u8 = 16;
+
+/// 牛顿迭代的结果状态
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefineStatus {
+    /// 残差不超过 `accuracy`
+    Converged,
+    /// 迭代不再减小残差，残差仍大于 `accuracy`
+    Stalled,
+    /// 最终位置处雅可比矩阵奇异（例如切线相交），参数的精度无法估计
+    Singular,
+}
+
+/// 牛顿迭代修正后的相交位置
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Refinement<F = f64> {
+    pub t1: F,
+    pub t2: F,
+    pub point1: [F; 2],
+    pub point2: [F; 2],
+    /// 两条曲线上对应点的距离 `|B1(t1) - B2(t2)|`
+    pub residual: F,
+    /// 参数误差 `|(t1, t2) - (t1*, t2*)|` 的一阶上界 `residual / σ_min(J)`，
+    /// `J = [B1'(t1), -B2'(t2)]`，`σ_min` 为其最小奇异值。奇异时为无穷大
+    pub error_bound: F,
+    pub status: RefineStatus,
+    pub iterations: u8,
+}
+
+/// 带有残差和参数误差上界的相交点，`location` 格式与 `get_intersections` 相同
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RefinedLocation<F = f64> {
+    pub location: [F; 8],
+    pub residual: F,
+    pub error_bound: F,
+    pub status: RefineStatus,
+}
+
+/// 两点距离
+fn distance<F: Float>(p1: [F; 2], p2: [F; 2]) -> F {
+    let dx = p1[0] - p2[0];
+    let dy = p1[1] - p2[1];
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// 雅可比矩阵 `[[a, b], [c, d]] = [B1'(t1), -B2'(t2)]` 及其行列式，奇异时返回 None
+fn jacobian<F: Float>(v1: &[F], v2: &[F], t1: F, t2: F) -> Option<([F; 4], F)> {
+    let d1 = evaluate(v1, t1, 1)?;
+    let d2 = evaluate(v2, t2, 1)?;
+    let (a, b, c, d) = (d1[0], -d2[0], d1[1], -d2[1]);
+    let det = a * d - b * c;
+    let scale = (a * a + c * c).sqrt() * (b * b + d * d).sqrt();
+    if det.abs() <= F::EPSILON * scale || det.is_nan() {
+        return None;
+    }
+    Some(([a, b, c, d], det))
+}
+
+/// 求解 `B1'(t1) Δt1 - B2'(t2) Δt2 = B2(t2) - B1(t1)`，雅可比矩阵奇异时返回 None
+fn newton_step<F: Float>(v1: &[F], v2: &[F], t1: F, t2: F) -> Option<(F, F)> {
+    let p1 = evaluate(v1, t1, 0)?;
+    let p2 = evaluate(v2, t2, 0)?;
+    let ([a, b, c, d], det) = jacobian(v1, v2, t1, t2)?;
+    let (gx, gy) = (p1[0] - p2[0], p1[1] - p2[1]);
+    Some(((b * gy - d * gx) / det, (c * gx - a * gy) / det))
+}
+
+/// 参数误差的一阶上界 `residual / σ_min(J)`，雅可比矩阵奇异时为无穷大
+///
+/// 二阶矩阵的 `σ_max² = (S + √(S² - 4 det²)) / 2`，`S` 为各元素的平方和，`σ_min = |det| / σ_max`
+fn error_bound<F: Float>(v1: &[F], v2: &[F], t1: F, t2: F, residual: F) -> F {
+    let Some(([a, b, c, d], det)) = jacobian(v1, v2, t1, t2) else {
+        return F::INFINITY;
+    };
+    let two = F::from_f64(2.0);
+    let sum = a * a + b * b + c * c + d * d;
+    let gap = (sum * sum - two * two * det * det).max(F::ZERO).sqrt();
+    let sigma_max = ((sum + gap) / two).sqrt();
+    residual * sigma_max / det.abs()
+}
+
+/// 以裁剪得到的参数为初值，对 `B1(t1) - B2(t2) = 0` 做二维牛顿迭代
+///
+/// 残差不超过 `accuracy` 或迭代不再收敛时停止。结果附带参数误差的一阶上界 `error_bound`，
+/// 需要参数精度的调用方可以要求它不超过目标值。切线相交时雅可比矩阵奇异，
+/// 保留初值并标记为 `RefineStatus::Singular`
+pub fn refine_intersection<F: Float>(
+    v1: &Curve<F>,
+    v2: &Curve<F>,
+    t1: F,
+    t2: F,
+    accuracy: F,
+) -> Refinement<F> {
+    let point1 = evaluate(v1, t1, 0).unwrap_or([F::ZERO; 2]);
+    let point2 = evaluate(v2, t2, 0).unwrap_or([F::ZERO; 2]);
+    let mut best = Refinement {
+        t1,
+        t2,
+        point1,
+        point2,
+        residual: distance(point1, point2),
+        error_bound: F::INFINITY,
+        status: RefineStatus::Singular,
+        iterations: 0,
+    };
+
+    for iteration in 1..=MAX_ITERATIONS {
+        let (dt1, dt2) = match newton_step(v1, v2, best.t1, best.t2) {
+            Some(step) => step,
+            None => break,
+        };
+        if best.residual <= accuracy {
+            break;
+        }
+        let t1 = (best.t1 + dt1).clamp(F::ZERO, F::ONE);
+        let t2 = (best.t2 + dt2).clamp(F::ZERO, F::ONE);
+        let (point1, point2) = match (evaluate(v1, t1, 0), evaluate(v2, t2, 0)) {
+            (Some(p1), Some(p2)) => (p1, p2),
+            _ => break,
+        };
+        let residual = distance(point1, point2);
+        if residual.is_nan() || residual >= best.residual {
+            break;
+        }
+        best = Refinement {
+            t1,
+            t2,
+            point1,
+            point2,
+            residual,
+            iterations: iteration,
+            ..best
+        };
+    }
+
+    best.error_bound = error_bound(v1, v2, best.t1, best.t2, best.residual);
+    if best.error_bound < F::INFINITY {
+        best.status = if best.residual <= accuracy {
+            RefineStatus::Converged
+        } else {
+            RefineStatus::Stalled
+        };
+    }
+    best
+}
+
+/// 对求交结果逐个修正，自交点只计算残差和参数误差上界不做迭代
+pub fn refine_locations<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    is_self: bool,
+    locations: &[[F; 8]],
+    accuracy: F,
+) -> Vec<RefinedLocation<F>> {
+    locations
+        .iter()
+        .map(|location| {
+            let i1 = location[1].to_f64() as usize;
+            let i2 = location[5].to_f64() as usize;
+            let (v1, v2) = (&curves1[i1], &curves2[i2]);
+            // 自交点牛顿迭代会收敛到 t1 = t2 的平凡解
+            let same_curve = is_self && i1 == i2;
+            let limit = if same_curve { F::INFINITY } else { accuracy };
+            let mut refined = refine_intersection(v1, v2, location[0], location[4], limit);
+            if same_curve
+                && refined.status == RefineStatus::Converged
+                && refined.residual > accuracy
+            {
+                refined.status = RefineStatus::Stalled;
+            }
+            RefinedLocation {
+                location: [
+                    refined.t1,
+                    location[1],
+                    refined.point1[0],
+                    refined.point1[1],
+                    refined.t2,
+                    location[5],
+                    refined.point2[0],
+                    refined.point2[1],
+                ],
+                residual: refined.residual,
+                error_bound: refined.error_bound,
+                status: refined.status,
+            }
+        })
+        .collect()
+}
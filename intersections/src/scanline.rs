@@ -0,0 +1,261 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+use crate::clip::chains;
+use crate::collision_detection::CollisionDetection;
+use crate::{bezier_coeffs, cubic_roots, evaluate, split_cubic_bezier_part, Bvh, Curve, Float};
+
+/// `hatch_lines` 最多生成的平行线条数
+pub const MAX_HATCH_LINES: u64 = 1 << 20;
+
+/// 判断点是否在闭合路径内部的规则
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// 环绕数不为零
+    #[default]
+    NonZero,
+    /// 环绕数为奇数
+    EvenOdd,
+}
+
+impl FillRule {
+    /// 环绕数为 `winding` 的点是否在内部
+    pub fn contains(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// 水平扫描线与曲线的交点
+///
+/// `winding` 为 1 表示曲线沿 y 增大的方向穿过扫描线，-1 表示沿 y 减小的方向，`t` 为在原曲线上的参数
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScanlineCrossing<F = f64> {
+    pub x: F,
+    pub winding: i8,
+    pub curve: u32,
+    pub t: F,
+}
+
+/// 在 y 方向上单调的曲线片段，对应原曲线上的参数区间 `[t_min, t_max]`
+#[derive(Clone, Copy, Debug)]
+struct Monotone<F> {
+    curve: u32,
+    v: Curve<F>,
+    t_min: F,
+    t_max: F,
+}
+
+impl<F: Float> Monotone<F> {
+    /// 片段上 y 等于 `y` 的参数，片段单调时只有一个
+    ///
+    /// 牛顿迭代超出当前区间时改用二分，区间每次至少缩小一半
+    fn solve(&self, y: F) -> F {
+        let v = &self.v;
+        // 经过端点时直接返回，交点与端点精确重合
+        if y == v[1] {
+            return F::ZERO;
+        }
+        if y == v[7] {
+            return F::ONE;
+        }
+        let increasing = v[7] > v[1];
+        let (mut lo, mut hi) = (F::ZERO, F::ONE);
+        let mut t = (y - v[1]) / (v[7] - v[1]);
+        for _ in 0..64 {
+            if t.is_nan() || t <= lo || t >= hi {
+                t = (lo + hi) / F::from_f64(2.0);
+            }
+            let (Some(p), Some(d)) = (evaluate(v, t, 0), evaluate(v, t, 1)) else {
+                break;
+            };
+            if p[1] == y {
+                return t;
+            }
+            if (p[1] < y) == increasing {
+                lo = t;
+            } else {
+                hi = t;
+            }
+            if hi - lo <= F::MACHINE_EPSILON {
+                break;
+            }
+            t -= (p[1] - y) / d[1];
+        }
+        if t.is_nan() || t < lo || t > hi {
+            (lo + hi) / F::from_f64(2.0)
+        } else {
+            t
+        }
+    }
+}
+
+/// 水平扫描线查询，构建时把曲线切分为 y 方向单调的片段，之后可以反复查询不同的 `y`
+#[derive(Clone, Debug)]
+pub struct Scanline<F = f64> {
+    pieces: Vec<Monotone<F>>,
+    bvh: Bvh<F>,
+}
+
+impl<F: Float> Scanline<F> {
+    pub fn new(curves: &[Curve<F>]) -> Self {
+        let mut pieces = vec![];
+        for (i, v) in curves.iter().enumerate() {
+            // dy/dt 的根把曲线分成单调的片段
+            let c = bezier_coeffs(v[1], v[3], v[5], v[7]);
+            let (two, three) = (F::from_f64(2.0), F::from_f64(3.0));
+            let mut ts: Vec<F> = cubic_roots(&[F::ZERO, three * c[0], two * c[1], c[2]])
+                .into_iter()
+                .filter(|&t| t > F::CURVETIME_EPSILON && t < F::ONE - F::CURVETIME_EPSILON)
+                .collect();
+            ts.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+            let mut t_min = F::ZERO;
+            for t_max in ts.into_iter().chain(core::iter::once(F::ONE)) {
+                let piece = split_cubic_bezier_part(v, t_min, t_max);
+                // 水平的片段不与扫描线相交
+                if piece[1] != piece[7] {
+                    pieces.push(Monotone {
+                        curve: i as u32,
+                        v: piece,
+                        t_min,
+                        t_max,
+                    });
+                }
+                t_min = t_max;
+            }
+        }
+        let bounds: Vec<[F; 4]> = pieces
+            .iter()
+            .map(|piece| CollisionDetection::get_curve_bounds(&piece.v))
+            .collect();
+        Scanline {
+            bvh: Bvh::new(&bounds),
+            pieces,
+        }
+    }
+
+    /// 扫描线 `y` 与曲线的交点，按 x 从小到大排列
+    ///
+    /// 每个片段只计入 y 范围 `[min, max)` 内的交点，经过片段连接点时只算一次，
+    /// 经过 y 方向的极值点时算两次或不算，对环绕数的贡献都正确
+    pub fn crossings(&self, y: F) -> Vec<ScanlineCrossing<F>> {
+        let mut crossings = vec![];
+        let rect = [F::NEG_INFINITY, y, F::INFINITY, y];
+        self.bvh.query(rect, F::ZERO, |i| {
+            let piece = &self.pieces[i as usize];
+            let (y0, y1) = (piece.v[1], piece.v[7]);
+            if y < y0.min(y1) || y >= y0.max(y1) {
+                return;
+            }
+            let t = piece.solve(y);
+            let Some(p) = evaluate(&piece.v, t, 0) else {
+                return;
+            };
+            crossings.push(ScanlineCrossing {
+                x: p[0],
+                winding: if y1 > y0 { 1 } else { -1 },
+                curve: piece.curve,
+                t: piece.t_min + (piece.t_max - piece.t_min) * t,
+            });
+        });
+        crossings.sort_by(|a, b| {
+            a.x.partial_cmp(&b.x)
+                .unwrap_or(Ordering::Equal)
+                .then(a.curve.cmp(&b.curve))
+                .then(a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal))
+        });
+        crossings
+    }
+
+    /// 扫描线 `y` 位于路径内部的区间 `[x1, x2]`
+    pub fn spans(&self, y: F, fill_rule: FillRule) -> Vec<[F; 2]> {
+        let mut spans: Vec<[F; 2]> = vec![];
+        let mut winding = 0;
+        for crossing in self.crossings(y) {
+            let inside = fill_rule.contains(winding);
+            winding += crossing.winding as i32;
+            match (inside, fill_rule.contains(winding)) {
+                (false, true) => spans.push([crossing.x, crossing.x]),
+                (true, false) => spans.last_mut().unwrap()[1] = crossing.x,
+                _ => {}
+            }
+        }
+        spans.retain(|span| span[1] > span[0]);
+        spans
+    }
+}
+
+/// 用间距为 `spacing`、与 x 轴夹角为 `angle`（弧度）的平行线填充闭合路径内部，返回线段 `[x1, y1, x2, y2]`
+///
+/// 不相接的首尾按直线闭合。平行线位于到原点距离为 `spacing` 整数倍的位置，相邻的图形使用相同参数时线条对齐。
+/// `spacing` 不是有限正数、路径坐标不是有限值或平行线超过 `MAX_HATCH_LINES` 条时返回空
+pub fn hatch_lines<F: Float>(
+    curves: &[Curve<F>],
+    spacing: F,
+    angle: F,
+    fill_rule: FillRule,
+) -> Vec<[F; 4]> {
+    if spacing <= F::ZERO || spacing.is_nan() || spacing == F::INFINITY {
+        return vec![];
+    }
+    // 旋转 -angle 后平行线变为水平
+    let (cos, sin) = (angle.cos(), angle.sin());
+    let rotated: Vec<Curve<F>> = chains(curves, true)
+        .into_iter()
+        .flatten()
+        .map(|v| {
+            let mut r = v;
+            for k in 0..4 {
+                let (x, y) = (v[k * 2], v[k * 2 + 1]);
+                r[k * 2] = x * cos + y * sin;
+                r[k * 2 + 1] = y * cos - x * sin;
+            }
+            r
+        })
+        .collect();
+    let (mut min_y, mut max_y) = (F::INFINITY, F::NEG_INFINITY);
+    for v in &rotated {
+        let bounds = CollisionDetection::get_curve_bounds(v);
+        min_y = min_y.min(bounds[1]);
+        max_y = max_y.max(bounds[3]);
+    }
+    // 线的序号超过 2^53 时相邻的整数无法区分，坐标为无穷大或 NaN 时也在这里排除
+    let limit = 9007199254740992.0;
+    let (low, high) = ((min_y / spacing).to_f64(), (max_y / spacing).to_f64());
+    if rotated.is_empty() || !(low > -limit && high < limit) {
+        return vec![];
+    }
+    // 向零取整后修正为向上、向下取整
+    let (mut first, mut last) = (low as i64, high as i64);
+    if (first as f64) < low {
+        first += 1;
+    }
+    if (last as f64) > high {
+        last -= 1;
+    }
+    if last - first >= MAX_HATCH_LINES as i64 {
+        return vec![];
+    }
+
+    let scanline = Scanline::new(&rotated);
+    let mut lines = vec![];
+    for k in first..=last {
+        let y = spacing * F::from_f64(k as f64);
+        for [x1, x2] in scanline.spans(y, fill_rule) {
+            lines.push([
+                x1 * cos - y * sin,
+                x1 * sin + y * cos,
+                x2 * cos - y * sin,
+                x2 * sin + y * cos,
+            ]);
+        }
+    }
+    lines
+}
@@ -0,0 +1,108 @@
+use crate::{is_curve_zero, is_zero, Curve, Float};
+
+/// 单条曲线的自交情况
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelfIntersection<F = f64> {
+    /// 没有自交点
+    None,
+    /// 曲线在 `t1 < t2` 两处经过同一点，形成环
+    Loop { t1: F, t2: F },
+    /// 环在端点处闭合，`t1` 为 0 或 `t2` 为 1
+    EndpointLoop { t1: F, t2: F },
+    /// 曲线内部的尖点，切线方向在 `t` 处反转
+    Cusp { t: F },
+}
+
+impl<F: Float> SelfIntersection<F> {
+    /// 自交点在曲线上的两个参数，尖点的两个参数相同
+    pub fn parameters(&self) -> Option<[F; 2]> {
+        match *self {
+            SelfIntersection::None => None,
+            SelfIntersection::Loop { t1, t2 } | SelfIntersection::EndpointLoop { t1, t2 } => {
+                Some([t1, t2])
+            }
+            SelfIntersection::Cusp { t } => Some([t, t]),
+        }
+    }
+}
+
+/// 参数在容差内位于 [0, 1] 时返回夹紧后的值，靠近端点的吸附到端点
+fn clamp_parameter<F: Float>(t: F) -> Option<F> {
+    if t < -F::CURVETIME_EPSILON || t > F::ONE + F::CURVETIME_EPSILON {
+        None
+    } else if is_curve_zero(t) {
+        Some(F::ZERO)
+    } else if is_curve_zero(F::ONE - t) {
+        Some(F::ONE)
+    } else {
+        Some(t)
+    }
+}
+
+/// 位于端点的尖点来自长度为零的手柄，不算自交
+fn cusp<F: Float>(t: F) -> SelfIntersection<F> {
+    if t > F::CURVETIME_EPSILON && t < F::ONE - F::CURVETIME_EPSILON {
+        SelfIntersection::Cusp { t }
+    } else {
+        SelfIntersection::None
+    }
+}
+
+/// 按 Loop-Blinn 分类求单条曲线的自交点
+///
+/// 判别式小于零时曲线所在的三次曲线有环，两个根都在 [0, 1] 内才会自交，落在端点上时为 `EndpointLoop`。
+/// 判别式接近零，或环小到两个根无法区分时为尖点
+pub fn get_self_intersection<F: Float>(v: &Curve<F>) -> SelfIntersection<F> {
+    let (x0, y0, x1, y1, x2, y2, x3, y3) = (v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7]);
+    if x0 == x1 && y0 == y1 && x2 == x3 && y2 == y3 {
+        return SelfIntersection::None;
+    }
+
+    let three = F::from_f64(3.0);
+    let a1 = x0 * (y3 - y2) + y0 * (x2 - x3) + x3 * y2 - y3 * x2;
+    let a2 = x1 * (y0 - y3) + y1 * (x3 - x0) + x0 * y3 - y0 * x3;
+    let a3 = x2 * (y1 - y0) + y2 * (x0 - x1) + x1 * y0 - y1 * x0;
+
+    let d3 = three * a3;
+    let d2 = d3 - a2;
+    let d1 = d2 - a2 + a1;
+
+    let l = (d1 * d1 + d2 * d2 + d3 * d3).sqrt();
+    let s = if l != F::ZERO { F::ONE / l } else { F::ZERO };
+
+    let d1 = d1 * s;
+    let d2 = d2 * s;
+    let d3 = d3 * s;
+
+    // 直线、二次曲线或只有一个拐点的曲线
+    if is_zero(d1) {
+        return SelfIntersection::None;
+    }
+
+    let d = three * d2 * d2 - F::from_f64(4.0) * d1 * d3;
+    // 有两个拐点的曲线
+    if d > F::EPSILON {
+        return SelfIntersection::None;
+    }
+    let f2 = F::from_f64(2.0) * d1;
+    if d >= -F::EPSILON {
+        return cusp(d2 / f2);
+    }
+
+    let f1 = (-d).sqrt();
+    let (mut t1, mut t2) = ((d2 + f1) / f2, (d2 - f1) / f2);
+    if t1 > t2 {
+        (t1, t2) = (t2, t1);
+    }
+    if t2 - t1 <= F::CURVETIME_EPSILON {
+        return cusp((t1 + t2) / F::from_f64(2.0));
+    }
+
+    match (clamp_parameter(t1), clamp_parameter(t2)) {
+        (Some(t1), Some(t2)) if t1 == F::ZERO || t2 == F::ONE => {
+            SelfIntersection::EndpointLoop { t1, t2 }
+        }
+        (Some(t1), Some(t2)) => SelfIntersection::Loop { t1, t2 },
+        _ => SelfIntersection::None,
+    }
+}
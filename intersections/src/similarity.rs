@@ -0,0 +1,361 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::collision_detection::CollisionDetection;
+use crate::distance::{Segment, MAX_STEPS};
+use crate::{bezier_coeffs, evaluate, split_cubic_bezier, ClosestPoints, Curve, Float};
+
+/// 细分曲线时的最大深度，每条曲线最多产生 2^16 个片段
+const MAX_DEPTH: u32 = 16;
+
+/// 计算弗雷歇距离时每条路径最多细分出的点数，动态规划表最多有 `MAX_FRECHET_POINTS²` 个格子
+pub const MAX_FRECHET_POINTS: usize = 1 << 12;
+
+/// 精度必须是有限的正数，否则细分或分支定界会一直进行到上限
+fn valid_precision<F: Float>(precision: F) -> bool {
+    precision > F::ZERO && precision < F::INFINITY
+}
+
+/// 点在曲线上的投影
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Projection<F = f64> {
+    pub t: F,
+    pub point: [F; 2],
+    pub distance: F,
+}
+
+/// 按降幂排列的多项式在 `t` 处的值
+fn horner<F: Float>(coeffs: &[F], t: F) -> F {
+    let mut value = F::ZERO;
+    for &c in coeffs {
+        value = value * t + c;
+    }
+    value
+}
+
+/// 按降幂排列的多项式在 `[0, 1]` 内的实根
+///
+/// 导数的根把区间分成单调的几段，每段两端异号时二分求根，逐次降阶直到一次多项式
+fn polynomial_roots<F: Float>(coeffs: &[F]) -> Vec<F> {
+    let degree = coeffs.len() - 1;
+    if degree == 0 {
+        return vec![];
+    }
+    let derivative: Vec<F> = coeffs[..degree]
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| c * F::from_f64((degree - i) as f64))
+        .collect();
+    let mut ends = vec![F::ZERO];
+    ends.extend(polynomial_roots(&derivative));
+    ends.push(F::ONE);
+
+    let mut roots = vec![];
+    for pair in ends.windows(2) {
+        let (mut lo, mut hi) = (pair[0], pair[1]);
+        let (f_lo, f_hi) = (horner(coeffs, lo), horner(coeffs, hi));
+        if f_lo == F::ZERO {
+            roots.push(lo);
+            continue;
+        }
+        if (f_lo < F::ZERO) == (f_hi < F::ZERO) {
+            continue;
+        }
+        // 二分到区间不再缩小为止
+        loop {
+            let mid = (lo + hi) / F::from_f64(2.0);
+            if mid <= lo || mid >= hi {
+                break;
+            }
+            if (horner(coeffs, mid) < F::ZERO) == (f_lo < F::ZERO) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        roots.push(lo);
+    }
+    roots
+}
+
+/// 曲线上离 `point` 最近的点
+///
+/// 最近点要么是端点，要么是 `(B(t) - point)·B'(t)` 这个五次多项式在 `[0, 1]` 内的根，
+/// 逐一比较后得到全局最近点，不会停在局部极小值上
+pub fn project_point<F: Float>(v: &Curve<F>, point: [F; 2]) -> Projection<F> {
+    let (two, three) = (F::from_f64(2.0), F::from_f64(3.0));
+    let mut coeffs = [F::ZERO; 6];
+    for k in 0..2 {
+        let [a, b, c, d] = bezier_coeffs(v[k], v[k + 2], v[k + 4], v[k + 6]);
+        let d = d - point[k];
+        coeffs[0] += three * a * a;
+        coeffs[1] += F::from_f64(5.0) * a * b;
+        coeffs[2] += F::from_f64(4.0) * a * c + two * b * b;
+        coeffs[3] += three * (b * c + a * d);
+        coeffs[4] += c * c + two * b * d;
+        coeffs[5] += c * d;
+    }
+    let mut nearest = Projection {
+        t: F::ZERO,
+        point: [v[0], v[1]],
+        distance: F::INFINITY,
+    };
+    let mut candidates = polynomial_roots(&coeffs);
+    candidates.extend([F::ZERO, F::ONE]);
+    for t in candidates {
+        if let Some(p) = evaluate(v, t, 0) {
+            let (dx, dy) = (p[0] - point[0], p[1] - point[1]);
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance < nearest.distance {
+                nearest = Projection {
+                    t,
+                    point: p,
+                    distance,
+                };
+            }
+        }
+    }
+    nearest
+}
+
+/// 一组曲线上离 `point` 最近的点及所在曲线的序号，边界框比当前结果更远的曲线直接跳过
+fn project_onto<F: Float>(curves: &[Curve<F>], point: [F; 2]) -> Option<(u32, Projection<F>)> {
+    let mut nearest: Option<(u32, Projection<F>)> = None;
+    for (i, v) in curves.iter().enumerate() {
+        if let Some((_, best)) = nearest {
+            let [min_x, min_y, max_x, max_y] = CollisionDetection::get_curve_bounds(v);
+            let dx = (min_x - point[0]).max(point[0] - max_x).max(F::ZERO);
+            let dy = (min_y - point[1]).max(point[1] - max_y).max(F::ZERO);
+            if dx * dx + dy * dy >= best.distance * best.distance {
+                continue;
+            }
+        }
+        let projection = project_point(v, point);
+        if nearest.is_none_or(|(_, best)| projection.distance < best.distance) {
+            nearest = Some((i as u32, projection));
+        }
+    }
+    nearest
+}
+
+/// 按上界从大到小出堆的片段
+struct Piece<F> {
+    bound: F,
+    segment: Segment<F>,
+}
+
+impl<F: Float> PartialEq for Piece<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Float> Eq for Piece<F> {}
+
+impl<F: Float> PartialOrd for Piece<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for Piece<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound
+            .partial_cmp(&other.bound)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// `from` 上的点到 `to` 的最大距离，`point1` 在 `from` 上
+///
+/// 片段上任意一点到 `to` 的距离不超过中点的距离加上中点到控制点的最大距离，
+/// 每次分割上界最大的片段，上界不超过当前结果加 `precision` 时停止
+fn directed_hausdorff<F: Float>(
+    from: &[Curve<F>],
+    to: &[Curve<F>],
+    precision: F,
+) -> Option<ClosestPoints<F>> {
+    let mut best: Option<ClosestPoints<F>> = None;
+    // 更新最远点，返回 `point` 到 `to` 的距离
+    let visit = |best: &mut Option<ClosestPoints<F>>, curve: u32, (t, point): (F, [F; 2])| {
+        let (curve2, projection) = project_onto(to, point)?;
+        if best.is_none_or(|best| projection.distance > best.distance) {
+            *best = Some(ClosestPoints {
+                distance: projection.distance,
+                curve1: curve,
+                t1: t,
+                point1: point,
+                curve2,
+                t2: projection.t,
+                point2: projection.point,
+                truncated: false,
+            });
+        }
+        Some(projection.distance)
+    };
+
+    let mut heap = BinaryHeap::new();
+    for (i, v) in from.iter().enumerate() {
+        let segment = Segment::new(i, v);
+        let [start, mid, end] = segment.samples();
+        visit(&mut best, segment.curve, start)?;
+        visit(&mut best, segment.curve, end)?;
+        let distance = visit(&mut best, segment.curve, mid)?;
+        heap.push(Piece {
+            bound: distance + radius(&segment.v, mid.1),
+            segment,
+        });
+    }
+
+    let mut steps = 0;
+    while let Some(Piece { bound, segment }) = heap.pop() {
+        let current = best.map_or(F::ZERO, |best| best.distance);
+        if bound <= current + precision {
+            break;
+        }
+        if steps >= MAX_STEPS {
+            if let Some(best) = &mut best {
+                best.truncated = true;
+            }
+            break;
+        }
+        steps += 1;
+        if !segment.splittable() {
+            continue;
+        }
+        let (left, right) = segment.split();
+        for segment in [left, right] {
+            let [_, mid, _] = segment.samples();
+            let distance = visit(&mut best, segment.curve, mid)?;
+            heap.push(Piece {
+                bound: distance + radius(&segment.v, mid.1),
+                segment,
+            });
+        }
+    }
+    best
+}
+
+/// 以 `center` 为圆心包含全部控制点的最小半径
+fn radius<F: Float>(v: &Curve<F>, center: [F; 2]) -> F {
+    let mut max = F::ZERO;
+    for k in 0..4 {
+        let (dx, dy) = (v[k * 2] - center[0], v[k * 2 + 1] - center[1]);
+        max = max.max(dx * dx + dy * dy);
+    }
+    max.sqrt()
+}
+
+/// 两组曲线之间的豪斯多夫距离及取得该距离的一对点，`point1` 总是在 `curves1` 上
+///
+/// 点到曲线的距离由全局投影求得，结果不超过真实值，且与真实值相差不超过 `precision`，任一组为空或 `precision` 不是有限正数时返回 `None`。
+/// 处理的片段数量达到上限时提前结束并标记 `truncated`，此时误差可能超过 `precision`
+pub fn hausdorff_distance<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    precision: F,
+) -> Option<ClosestPoints<F>> {
+    if !valid_precision(precision) {
+        return None;
+    }
+    let forward = directed_hausdorff(curves1, curves2, precision)?;
+    let backward = directed_hausdorff(curves2, curves1, precision)?;
+    let truncated = forward.truncated || backward.truncated;
+    Some(if backward.distance > forward.distance {
+        ClosestPoints {
+            distance: backward.distance,
+            curve1: backward.curve2,
+            t1: backward.t2,
+            point1: backward.point2,
+            curve2: backward.curve1,
+            t2: backward.t1,
+            point2: backward.point1,
+            truncated,
+        }
+    } else {
+        ClosestPoints {
+            truncated,
+            ..forward
+        }
+    })
+}
+
+fn subdivide<F: Float>(v: &Curve<F>, precision: F, depth: u32, points: &mut Vec<[F; 2]>) {
+    if points.len() > MAX_FRECHET_POINTS {
+        return;
+    }
+    let mut length = F::ZERO;
+    for k in 0..3 {
+        let (dx, dy) = (v[k * 2 + 2] - v[k * 2], v[k * 2 + 3] - v[k * 2 + 1]);
+        length += (dx * dx + dy * dy).sqrt();
+    }
+    if length <= precision || depth >= MAX_DEPTH {
+        points.push([v[6], v[7]]);
+        return;
+    }
+    let (left, right) = split_cubic_bezier(v, F::from_f64(0.5));
+    subdivide(&left, precision, depth + 1, points);
+    subdivide(&right, precision, depth + 1, points);
+}
+
+/// 把一组首尾相接的曲线细分为相邻点间弧长不超过 `precision` 的点列，点数超过 `MAX_FRECHET_POINTS` 时返回 `None`
+fn sample_curves<F: Float>(curves: &[Curve<F>], precision: F) -> Option<Vec<[F; 2]>> {
+    let mut points: Vec<[F; 2]> = vec![];
+    for v in curves {
+        if points.last() != Some(&[v[0], v[1]]) {
+            points.push([v[0], v[1]]);
+        }
+        subdivide(v, precision, 0, &mut points);
+    }
+    (points.len() <= MAX_FRECHET_POINTS).then_some(points)
+}
+
+/// 两个点列之间的离散弗雷歇距离，任一点列为空时返回 `None`
+pub fn discrete_frechet_distance<F: Float>(points1: &[[F; 2]], points2: &[[F; 2]]) -> Option<F> {
+    if points1.is_empty() || points2.is_empty() {
+        return None;
+    }
+    let distance = |p: [F; 2], q: [F; 2]| {
+        let (dx, dy) = (p[0] - q[0], p[1] - q[1]);
+        (dx * dx + dy * dy).sqrt()
+    };
+    // 只保留上一行的结果
+    let mut row = vec![F::ZERO; points2.len()];
+    for (i, &p) in points1.iter().enumerate() {
+        let mut diagonal = F::ZERO;
+        for (j, &q) in points2.iter().enumerate() {
+            let d = distance(p, q);
+            let reach = match (i, j) {
+                (0, 0) => F::ZERO,
+                (0, _) => row[j - 1],
+                (_, 0) => row[0],
+                _ => diagonal.min(row[j]).min(row[j - 1]),
+            };
+            diagonal = row[j];
+            row[j] = reach.max(d);
+        }
+    }
+    Some(row[points2.len() - 1])
+}
+
+/// 两组首尾相接的曲线之间弗雷歇距离的近似值
+///
+/// 两条路径细分为相邻点间弧长不超过 `precision` 的点列后计算离散弗雷歇距离。
+/// 点列相邻点间的弧长不超过 `precision`，结果与连续弗雷歇距离相差不超过 `precision`，
+/// 所以不再对点列连成的折线单独计算连续弗雷歇距离。
+/// 耗时与两个点列长度的乘积成正比，`precision` 不是有限正数或任一路径细分后超过 `MAX_FRECHET_POINTS` 个点时返回 `None`
+pub fn frechet_distance<F: Float>(
+    curves1: &[Curve<F>],
+    curves2: &[Curve<F>],
+    precision: F,
+) -> Option<F> {
+    if !valid_precision(precision) {
+        return None;
+    }
+    discrete_frechet_distance(
+        &sample_curves(curves1, precision)?,
+        &sample_curves(curves2, precision)?,
+    )
+}
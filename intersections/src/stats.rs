@@ -0,0 +1,62 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// 两条曲线求交时实际执行的分支
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CurveBranch {
+    /// 边界框不相交或两条曲线重合，没有进入任何分支
+    Skipped,
+    LineLine,
+    LineCurve,
+    /// 贝塞尔裁剪，附带裁剪次数和最大深度
+    CurveCurve {
+        calls: u16,
+        recursion: u8,
+    },
+}
+
+/// 求交过程的统计数据
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// 边界框碰撞检测得到的候选曲线对数量
+    pub candidate_pairs: u32,
+    /// 至少有一个相交点的曲线对数量
+    pub intersecting_pairs: u32,
+    /// 直线与直线分支的执行次数
+    pub line_line: u32,
+    /// 直线与曲线分支的执行次数
+    pub line_curve: u32,
+    /// 曲线与曲线分支（贝塞尔裁剪）的执行次数
+    pub curve_curve: u32,
+    /// 贝塞尔裁剪的总次数
+    pub clip_calls: u32,
+    /// 单个曲线对的最大裁剪次数
+    pub max_clip_calls: u32,
+    /// 各曲线对最大裁剪深度之和，除以 `curve_curve` 得到平均深度
+    pub total_recursion: u32,
+    /// 最大裁剪深度
+    pub max_recursion: u32,
+}
+
+impl Stats {
+    /// 记录一个候选曲线对的求交结果
+    pub(crate) fn record(&mut self, branch: CurveBranch, intersected: bool) {
+        self.candidate_pairs += 1;
+        if intersected {
+            self.intersecting_pairs += 1;
+        }
+        match branch {
+            CurveBranch::Skipped => {}
+            CurveBranch::LineLine => self.line_line += 1,
+            CurveBranch::LineCurve => self.line_curve += 1,
+            CurveBranch::CurveCurve { calls, recursion } => {
+                self.curve_curve += 1;
+                self.clip_calls += calls as u32;
+                self.max_clip_calls = self.max_clip_calls.max(calls as u32);
+                self.total_recursion += recursion as u32;
+                self.max_recursion = self.max_recursion.max(recursion as u32);
+            }
+        }
+    }
+}
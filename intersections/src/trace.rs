@@ -0,0 +1,160 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Curve, Float, Hull};
+
+/// 一步裁剪之后的处理
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipDecision {
+    /// 裁剪次数达到上限，停止求交
+    MaxCalls,
+    /// 裁剪深度达到上限，丢弃当前片段
+    MaxRecursion,
+    /// 两个片段共线，丢弃当前片段
+    Collinear,
+    /// 凸包与胖线不相交，片段中没有相交点
+    Miss,
+    /// 参数区间收敛，`reported` 为 false 表示两端都在端点上而被跳过
+    Converged { reported: bool },
+    /// 裁剪剩余超过 80%，将 `v1` 的剩余部分对半分割后交换角色
+    SplitClipped,
+    /// 裁剪剩余超过 80%，将 `v2` 对半分割后交换角色
+    SplitFatline,
+    /// 交换两个片段的角色继续裁剪
+    Swap,
+    /// `v2` 的参数区间已足够小，继续用 `v2` 裁剪 `v1`
+    Repeat,
+}
+
+impl ClipDecision {
+    fn name(self) -> &'static str {
+        match self {
+            ClipDecision::MaxCalls => "maxCalls",
+            ClipDecision::MaxRecursion => "maxRecursion",
+            ClipDecision::Collinear => "collinear",
+            ClipDecision::Miss => "miss",
+            ClipDecision::Converged { reported: true } => "converged",
+            ClipDecision::Converged { reported: false } => "convergedAtEndpoints",
+            ClipDecision::SplitClipped => "splitClipped",
+            ClipDecision::SplitFatline => "splitFatline",
+            ClipDecision::Swap => "swap",
+            ClipDecision::Repeat => "repeat",
+        }
+    }
+}
+
+/// 贝塞尔裁剪的一步：用 `v2` 的胖线裁剪 `v1`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClipStep<F = f64> {
+    /// 第几次裁剪，从 1 开始
+    pub call: u16,
+    /// 裁剪深度
+    pub recursion: u8,
+    /// 为 true 时 `v1` 是第二条曲线的片段
+    pub flip: bool,
+    /// 被裁剪的片段，参数区间为 `t_range`
+    pub v1: Curve<F>,
+    /// 构造胖线的片段，参数区间为 `u_range`
+    pub v2: Curve<F>,
+    pub t_range: [F; 2],
+    pub u_range: [F; 2],
+    /// 胖线 `[d_min, d_max]`
+    pub fatline: Option<[F; 2]>,
+    /// `v1` 控制点到胖线基线的有向距离构成的凸包，依次为上半部分和下半部分
+    pub hull: Option<(Hull<F>, Hull<F>)>,
+    /// 裁剪结果 `[t_min_clip, t_max_clip]`，相对于 `v1`
+    pub clip: Option<[F; 2]>,
+    pub decision: ClipDecision,
+}
+
+impl<F: Float> ClipStep<F> {
+    pub(crate) fn decided(mut self, decision: ClipDecision) -> Self {
+        self.decision = decision;
+        self
+    }
+}
+
+/// 贝塞尔裁剪的跟踪钩子，默认的 `()` 不做任何记录，闭包会收到每一步
+pub trait ClipTracer<F> {
+    fn step(&mut self, step: &ClipStep<F>);
+}
+
+impl<F> ClipTracer<F> for () {
+    #[inline(always)]
+    fn step(&mut self, _: &ClipStep<F>) {}
+}
+
+impl<F, T: FnMut(&ClipStep<F>)> ClipTracer<F> for T {
+    fn step(&mut self, step: &ClipStep<F>) {
+        self(step)
+    }
+}
+
+/// 记录全部裁剪步骤
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClipTrace<F = f64> {
+    pub steps: Vec<ClipStep<F>>,
+}
+
+impl<F: Float> ClipTracer<F> for ClipTrace<F> {
+    fn step(&mut self, step: &ClipStep<F>) {
+        self.steps.push(*step);
+    }
+}
+
+fn number<F: Float>(v: F) -> String {
+    let v = v.to_f64();
+    if v.is_finite() {
+        format!("{v}")
+    } else {
+        "null".into()
+    }
+}
+
+fn numbers<F: Float>(values: &[F]) -> String {
+    let items: Vec<String> = values.iter().map(|&v| number(v)).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn hull_points<F: Float>(hull: &Hull<F>) -> String {
+    let items: Vec<String> = hull
+        .points()
+        .iter()
+        .map(|&(x, y)| numbers(&[x, y]))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+impl<F: Float> ClipTrace<F> {
+    /// 序列化为 JSON，字段名为驼峰形式，缺失的值为 `null`
+    pub fn to_json(&self) -> String {
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .map(|step| {
+                let fatline = step.fatline.map_or("null".into(), |v| numbers(&v));
+                let hull = step.hull.map_or("null".into(), |(top, bottom)| {
+                    format!(
+                        "{{\"top\":{},\"bottom\":{}}}",
+                        hull_points(&top),
+                        hull_points(&bottom)
+                    )
+                });
+                let clip = step.clip.map_or("null".into(), |v| numbers(&v));
+                format!(
+                    "{{\"call\":{},\"recursion\":{},\"flip\":{},\"v1\":{},\"v2\":{},\"tRange\":{},\"uRange\":{},\"fatline\":{fatline},\"hull\":{hull},\"clip\":{clip},\"decision\":\"{}\"}}",
+                    step.call,
+                    step.recursion,
+                    step.flip,
+                    numbers(&step.v1),
+                    numbers(&step.v2),
+                    numbers(&step.t_range),
+                    numbers(&step.u_range),
+                    step.decision.name()
+                )
+            })
+            .collect();
+        format!("{{\"steps\":[{}]}}", steps.join(","))
+    }
+}
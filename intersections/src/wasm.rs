@@ -0,0 +1,593 @@
+//! wasm 接口，曲线以扁平的 `[p1x, p1y, c1x, c1y, c2x, c2y, p2x, p2y, ...]` 数组传入
+use alloc::vec;
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+mod buffer;
+
+pub use buffer::{IntersectionBuffer, LocationColumns};
+
+#[cfg(feature = "wasm-serde")]
+use crate::Intersection;
+use crate::{
+    clip_to_grid, clip_to_rect, find_within_distance, first_hit, frechet_distance,
+    get_intersections_with_policy, get_refined_intersections, hatch_lines, hausdorff_distance,
+    intersect_infinite_line, intersect_ray, location_crossings, location_geometries,
+    min_path_distance, split_cubic_bezier, BoundsCollisions, Broadphase, ClosestPoints,
+    CollisionDetection, Curve, Deadline, EndpointPolicy, FillRule, IntersectionCursor,
+    IntersectionJob, RectSelection, RefinedLocation, Scanline, Stats, Tile, TileGrid, WorkBudget,
+};
+#[cfg(feature = "wasm-serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm-serde")]
+use tsify::Tsify;
+
+/// 相交点列表，以对象形式返回的接口的返回值
+#[cfg(feature = "wasm-serde")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi)]
+pub struct Intersections(pub Vec<Intersection>);
+
+/// 寻找两条路径的相交点，以对象形式返回，`with_geometry` 为 true 时附带切线、法线、曲率和交角
+#[cfg(feature = "wasm-serde")]
+#[wasm_bindgen]
+pub fn rust_get_muti_intersection_objects(
+    slice1: &[f64],
+    slice2: &[f64],
+    endpoint_policy: Option<EndpointPolicy>,
+    with_geometry: Option<bool>,
+) -> Intersections {
+    let mut locations = vec![];
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    let policy = endpoint_policy.unwrap_or_default();
+    get_intersections_with_policy(&curves1, &curves2, false, policy, &mut locations);
+    to_objects(&curves1, &curves2, false, &locations, with_geometry)
+}
+
+/// 寻找当前路径的相交点，以对象形式返回
+#[cfg(feature = "wasm-serde")]
+#[wasm_bindgen]
+pub fn rust_get_intersection_objects(
+    slice: &[f64],
+    endpoint_policy: Option<EndpointPolicy>,
+    with_geometry: Option<bool>,
+) -> Intersections {
+    let mut locations = vec![];
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    let policy = endpoint_policy.unwrap_or_default();
+    get_intersections_with_policy(&curves, &curves, true, policy, &mut locations);
+    to_objects(&curves, &curves, true, &locations, with_geometry)
+}
+
+#[cfg(feature = "wasm-serde")]
+fn to_objects(
+    curves1: &[Curve],
+    curves2: &[Curve],
+    is_self: bool,
+    locations: &[[f64; 8]],
+    with_geometry: Option<bool>,
+) -> Intersections {
+    let with_geometry = with_geometry.unwrap_or(false);
+    Intersections(
+        locations
+            .iter()
+            .map(|location| {
+                let intersection = Intersection::from_location(location, is_self);
+                if with_geometry {
+                    intersection.with_geometry(curves1, curves2)
+                } else {
+                    intersection
+                }
+            })
+            .collect(),
+    )
+}
+
+/// 批量计算相交点处的几何信息，`locations` 为求交接口返回的扁平数组，
+/// 当前路径求交时 `slice2` 传入与 `slice1` 相同的路径
+///
+/// 每个相交点依次为 `[tx1, ty1, nx1, ny1, k1, tx2, ty2, nx2, ny2, k2, angle, crossing]`，
+/// `crossing` 为 1 表示第二条曲线从第一条曲线左侧穿到右侧，-1 表示从右到左，0 表示没有穿过
+#[wasm_bindgen]
+pub fn rust_get_location_geometries(slice1: &[f64], slice2: &[f64], locations: &[f64]) -> Vec<f64> {
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    let locations: Vec<[f64; 8]> = to_vec_of_arrays(locations);
+    location_geometries(&curves1, &curves2, &locations)
+        .iter()
+        .flat_map(|geometry| geometry.to_array())
+        .collect()
+}
+
+/// 批量计算相交点处第二条曲线穿过第一条曲线的方向，参数与 `rust_get_location_geometries` 相同
+///
+/// 1 表示从左侧穿到右侧，-1 表示从右到左，0 表示没有穿过
+#[wasm_bindgen]
+pub fn rust_get_location_crossings(slice1: &[f64], slice2: &[f64], locations: &[f64]) -> Vec<i8> {
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    let locations: Vec<[f64; 8]> = to_vec_of_arrays(locations);
+    location_crossings(&curves1, &curves2, &locations)
+        .iter()
+        .map(|crossing| crossing.sign())
+        .collect()
+}
+
+/// 最近点的结果展开为 `[t1, i1, x1, y1, t2, i2, x2, y2, distance, truncated]`，
+/// `truncated` 为 1 时搜索提前结束，`distance` 只是上界
+fn closest_points_to_vec(result: ClosestPoints) -> Vec<f64> {
+    let mut values = result.to_array().to_vec();
+    values.push(if result.truncated { 1.0 } else { 0.0 });
+    values
+}
+
+/// 两条路径之间的最短距离，格式见 `closest_points_to_vec`，任一路径为空时返回空数组
+#[wasm_bindgen]
+pub fn rust_get_min_distance(slice1: &[f64], slice2: &[f64]) -> Vec<f64> {
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    min_path_distance(&curves1, &curves2).map_or(vec![], closest_points_to_vec)
+}
+
+/// 查找两条路径之间距离不超过 `threshold` 的一对点，格式与 `rust_get_min_distance` 相同，
+/// 找到后立即返回，没有时返回空数组，搜索提前结束而无法判断时返回 `undefined`
+#[wasm_bindgen]
+pub fn rust_find_within_distance(
+    slice1: &[f64],
+    slice2: &[f64],
+    threshold: f64,
+) -> Option<Vec<f64>> {
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    find_within_distance(&curves1, &curves2, threshold)
+        .ok()
+        .map(|result| result.map_or(vec![], closest_points_to_vec))
+}
+
+/// 两条路径之间的豪斯多夫距离，格式与 `rust_get_min_distance` 相同，`point1` 总是在第一条路径上，
+/// 任一路径为空或 `precision` 不是有限正数时返回空数组
+#[wasm_bindgen]
+pub fn rust_get_hausdorff_distance(slice1: &[f64], slice2: &[f64], precision: f64) -> Vec<f64> {
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    hausdorff_distance(&curves1, &curves2, precision).map_or(vec![], closest_points_to_vec)
+}
+
+/// 两条路径之间弗雷歇距离的近似值，误差不超过 `precision`，任一路径为空、`precision` 不是有限正数
+/// 或细分后的点数超过 `MAX_FRECHET_POINTS` 时返回 `undefined`
+#[wasm_bindgen]
+pub fn rust_get_frechet_distance(slice1: &[f64], slice2: &[f64], precision: f64) -> Option<f64> {
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    frechet_distance(&curves1, &curves2, precision)
+}
+
+/// 两组边界框之间的碰撞，每 4 个数为一个边界框 `[min_x, min_y, max_x, max_y]`，
+/// `is_self` 时忽略 `bounds2`，间隙不超过 `tolerance` 的也算碰撞，`broadphase` 缺省时扫描
+#[wasm_bindgen]
+pub fn rust_find_bounds_collisions(
+    bounds1: &[f64],
+    bounds2: &[f64],
+    is_self: bool,
+    tolerance: f64,
+    broadphase: Option<Broadphase>,
+) -> BoundsCollisions {
+    let bounds1: Vec<[f64; 4]> = to_vec_of_arrays(bounds1);
+    let bounds2: Vec<[f64; 4]> = to_vec_of_arrays(bounds2);
+    CollisionDetection::find_bounds_collisions(
+        &bounds1,
+        &bounds2,
+        is_self,
+        tolerance,
+        broadphase.unwrap_or_default(),
+    )
+}
+
+/// 包含点 `(x, y)` 的边界框序号，间隙不超过 `tolerance` 的也算包含
+#[wasm_bindgen]
+pub fn rust_find_bounds_at_point(bounds: &[f64], x: f64, y: f64, tolerance: f64) -> Vec<u32> {
+    let bounds: Vec<[f64; 4]> = to_vec_of_arrays(bounds);
+    CollisionDetection::find_bounds_at_point(&bounds, [x, y], tolerance)
+}
+
+/// 被矩形选中的边界框序号，`selection` 缺省时与矩形相交即选中
+#[wasm_bindgen]
+pub fn rust_find_bounds_in_rect(
+    bounds: &[f64],
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+    selection: Option<RectSelection>,
+) -> Vec<u32> {
+    let bounds: Vec<[f64; 4]> = to_vec_of_arrays(bounds);
+    CollisionDetection::find_bounds_in_rect(
+        &bounds,
+        [min_x, min_y, max_x, max_y],
+        selection.unwrap_or_default(),
+    )
+}
+
+/// 寻找两条路径的相交点（f64类型），`endpoint_policy` 缺省时排除两端都在端点上的接触
+#[wasm_bindgen]
+pub fn rust_get_muti_intersections(
+    slice1: &[f64],
+    slice2: &[f64],
+    endpoint_policy: Option<EndpointPolicy>,
+) -> Vec<f64> {
+    let mut locations = vec![];
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    let policy = endpoint_policy.unwrap_or_default();
+    get_intersections_with_policy(&curves1, &curves2, false, policy, &mut locations);
+    flatten(locations)
+}
+
+/// 寻找当前路径的相交点（f64类型）
+#[wasm_bindgen]
+pub fn rust_get_intersections(slice: &[f64], endpoint_policy: Option<EndpointPolicy>) -> Vec<f64> {
+    let mut locations = vec![];
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    let policy = endpoint_policy.unwrap_or_default();
+    get_intersections_with_policy(&curves, &curves, true, policy, &mut locations);
+    flatten(locations)
+}
+
+/// 寻找两条路径的相交点（f32类型）
+#[wasm_bindgen]
+pub fn rust_get_muti_intersections_f32(
+    slice1: &[f32],
+    slice2: &[f32],
+    endpoint_policy: Option<EndpointPolicy>,
+) -> Vec<f32> {
+    let mut locations = vec![];
+    let curves1: Vec<[f32; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f32; 8]> = to_vec_of_arrays(slice2);
+    let policy = endpoint_policy.unwrap_or_default();
+    get_intersections_with_policy(&curves1, &curves2, false, policy, &mut locations);
+    flatten(locations)
+}
+
+/// 寻找当前路径的相交点（f32类型）
+#[wasm_bindgen]
+pub fn rust_get_intersections_f32(
+    slice: &[f32],
+    endpoint_policy: Option<EndpointPolicy>,
+) -> Vec<f32> {
+    let mut locations = vec![];
+    let curves: Vec<[f32; 8]> = to_vec_of_arrays(slice);
+    let policy = endpoint_policy.unwrap_or_default();
+    get_intersections_with_policy(&curves, &curves, true, policy, &mut locations);
+    flatten(locations)
+}
+
+/// 修正后的相交点展开为 `[t1, i1, x1, y1, t2, i2, x2, y2, residual, errorBound, status]`
+///
+/// `errorBound` 为参数误差的一阶上界，奇异时为 `Infinity`，`status` 为 `RefineStatus` 的取值
+fn flatten_refined(locations: Vec<RefinedLocation>) -> Vec<f64> {
+    let mut res = Vec::with_capacity(locations.len() * 11);
+    for item in locations {
+        res.extend_from_slice(&item.location);
+        res.push(item.residual);
+        res.push(item.error_bound);
+        res.push(item.status as u8 as f64);
+    }
+    res
+}
+
+/// 寻找两条路径的相交点并修正，格式见 `flatten_refined`
+#[wasm_bindgen]
+pub fn rust_get_muti_refined_intersections(
+    slice1: &[f64],
+    slice2: &[f64],
+    accuracy: f64,
+    endpoint_policy: Option<EndpointPolicy>,
+) -> Vec<f64> {
+    let curves1: Vec<[f64; 8]> = to_vec_of_arrays(slice1);
+    let curves2: Vec<[f64; 8]> = to_vec_of_arrays(slice2);
+    let policy = endpoint_policy.unwrap_or_default();
+    flatten_refined(get_refined_intersections(
+        &curves1, &curves2, false, policy, accuracy,
+    ))
+}
+
+/// 寻找当前路径的相交点并修正，格式见 `flatten_refined`
+#[wasm_bindgen]
+pub fn rust_get_refined_intersections(
+    slice: &[f64],
+    accuracy: f64,
+    endpoint_policy: Option<EndpointPolicy>,
+) -> Vec<f64> {
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    let policy = endpoint_policy.unwrap_or_default();
+    flatten_refined(get_refined_intersections(
+        &curves, &curves, true, policy, accuracy,
+    ))
+}
+
+/// 分段求交任务，构建时复制路径并建立边界框的层次包围盒，之后每次 `resume` 在预算内继续
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct BudgetedIntersections {
+    curves1: Vec<Curve>,
+    curves2: Vec<Curve>,
+    is_self: bool,
+    job: IntersectionJob,
+}
+
+#[wasm_bindgen]
+impl BudgetedIntersections {
+    /// 寻找两条路径的相交点，`slice2` 缺省时寻找 `slice1` 自身的相交点
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        slice1: &[f64],
+        slice2: Option<Vec<f64>>,
+        endpoint_policy: Option<EndpointPolicy>,
+    ) -> Self {
+        let curves1: Vec<Curve> = to_vec_of_arrays(slice1);
+        let curves2: Vec<Curve> = slice2.as_deref().map_or(vec![], to_vec_of_arrays);
+        let is_self = slice2.is_none();
+        let job = IntersectionJob::new(
+            &curves1,
+            if is_self { &curves1 } else { &curves2 },
+            is_self,
+            endpoint_policy.unwrap_or_default(),
+        );
+        BudgetedIntersections {
+            curves1,
+            curves2,
+            is_self,
+            job,
+        }
+    }
+
+    /// 在预算内继续求交，返回本次新增的相交点，格式与 `rust_get_intersections` 相同
+    ///
+    /// `max_work` 为工作量上限，`deadline` 为 `Date.now()` 时间戳（毫秒），都缺省时直到完成
+    pub fn resume(&mut self, max_work: Option<u32>, deadline: Option<f64>) -> Vec<f64> {
+        let mut locations = vec![];
+        let mut budget = (
+            max_work.map(WorkBudget::new),
+            deadline.map(|deadline| Deadline::new(js_sys::Date::now, deadline)),
+        );
+        let curves2 = if self.is_self {
+            &self.curves1
+        } else {
+            &self.curves2
+        };
+        self.job
+            .resume(&self.curves1, curves2, &mut budget, &mut locations);
+        flatten(locations)
+    }
+
+    /// 是否已全部完成
+    #[wasm_bindgen(getter)]
+    pub fn finished(&self) -> bool {
+        self.job.is_finished()
+    }
+
+    /// 下一次继续的位置，为空表示已全部完成
+    #[wasm_bindgen(getter)]
+    pub fn cursor(&self) -> Option<IntersectionCursor> {
+        self.job.cursor()
+    }
+
+    /// 到目前为止累计的统计数据
+    #[wasm_bindgen(getter)]
+    pub fn stats(&self) -> Stats {
+        self.job.stats()
+    }
+}
+
+/// 裁剪后的路径，第 k 条路径的曲线为 `curves` 中第 `offsets[k]` 到 `offsets[k + 1]` 条，
+/// 按网格裁剪时第 i 个瓦片的路径为第 `tile_offsets[i]` 到 `tile_offsets[i + 1]` 条
+#[wasm_bindgen]
+pub struct ClippedPaths {
+    curves: Vec<f64>,
+    offsets: Vec<u32>,
+    tiles: Vec<u32>,
+    tile_offsets: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl ClippedPaths {
+    /// 所有路径的曲线，格式与输入相同
+    #[wasm_bindgen(getter)]
+    pub fn curves(&self) -> Vec<f64> {
+        self.curves.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn offsets(&self) -> Vec<u32> {
+        self.offsets.clone()
+    }
+
+    /// 每个瓦片的 `[column, row]`，只裁剪到矩形时为空
+    #[wasm_bindgen(getter)]
+    pub fn tiles(&self) -> Vec<u32> {
+        self.tiles.clone()
+    }
+
+    #[wasm_bindgen(getter = tileOffsets)]
+    pub fn tile_offsets(&self) -> Vec<u32> {
+        self.tile_offsets.clone()
+    }
+}
+
+impl ClippedPaths {
+    fn push_paths(&mut self, paths: Vec<Vec<Curve>>) {
+        for path in paths {
+            self.curves.extend(path.into_iter().flatten());
+            self.offsets.push((self.curves.len() / 8) as u32);
+        }
+    }
+}
+
+/// 把曲线裁剪到矩形内，`closed` 时把首尾相接的曲线看作闭合路径并沿边界补齐
+#[wasm_bindgen]
+pub fn rust_clip_to_rect(
+    slice: &[f64],
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+    closed: bool,
+) -> ClippedPaths {
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    let mut result = ClippedPaths {
+        curves: vec![],
+        offsets: vec![0],
+        tiles: vec![],
+        tile_offsets: vec![],
+    };
+    result.push_paths(clip_to_rect(&curves, [min_x, min_y, max_x, max_y], closed));
+    result
+}
+
+/// 把曲线分配到 `columns` × `rows` 个大小为 `tile_width` × `tile_height` 的瓦片并裁剪，
+/// 没有路径的瓦片不返回
+#[wasm_bindgen]
+pub fn rust_clip_to_grid(
+    slice: &[f64],
+    origin_x: f64,
+    origin_y: f64,
+    tile_width: f64,
+    tile_height: f64,
+    columns: u32,
+    rows: u32,
+    closed: bool,
+) -> ClippedPaths {
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    let grid = TileGrid {
+        origin: [origin_x, origin_y],
+        tile_size: [tile_width, tile_height],
+        columns,
+        rows,
+    };
+    let mut result = ClippedPaths {
+        curves: vec![],
+        offsets: vec![0],
+        tiles: vec![],
+        tile_offsets: vec![0],
+    };
+    for Tile { column, row, paths } in clip_to_grid(&curves, &grid, closed) {
+        result.tiles.extend([column, row]);
+        result.push_paths(paths);
+        result.tile_offsets.push((result.offsets.len() - 1) as u32);
+    }
+    result
+}
+
+/// 射线与路径的所有交点，每 5 个数为一个交点 `[t, i, x, y, distance]`，按到起点的距离排列
+#[wasm_bindgen]
+pub fn rust_intersect_ray(slice: &[f64], x: f64, y: f64, dx: f64, dy: f64) -> Vec<f64> {
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    intersect_ray([x, y], [dx, dy], &curves)
+        .iter()
+        .flat_map(|hit| hit.to_array())
+        .collect()
+}
+
+/// 直线与路径的所有交点，格式与 `rust_intersect_ray` 相同，在起点之前的交点距离为负
+#[wasm_bindgen]
+pub fn rust_intersect_infinite_line(slice: &[f64], x: f64, y: f64, dx: f64, dy: f64) -> Vec<f64> {
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    intersect_infinite_line([x, y], [dx, dy], &curves)
+        .iter()
+        .flat_map(|hit| hit.to_array())
+        .collect()
+}
+
+/// 射线遇到的第一个交点 `[t, i, x, y, distance]`，没有时返回空数组
+#[wasm_bindgen]
+pub fn rust_first_hit(slice: &[f64], x: f64, y: f64, dx: f64, dy: f64) -> Vec<f64> {
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    first_hit([x, y], [dx, dy], &curves).map_or(vec![], |hit| hit.to_array().to_vec())
+}
+
+/// 水平扫描线查询，构建一次后可以反复查询不同的 `y`
+#[wasm_bindgen]
+pub struct ScanlineIndex {
+    scanline: Scanline,
+}
+
+#[wasm_bindgen]
+impl ScanlineIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new(slice: &[f64]) -> Self {
+        let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+        ScanlineIndex {
+            scanline: Scanline::new(&curves),
+        }
+    }
+
+    /// 扫描线 `y` 与路径的交点，每 4 个数为一个交点 `[x, winding, i, t]`，按 x 从小到大排列
+    pub fn crossings(&self, y: f64) -> Vec<f64> {
+        self.scanline
+            .crossings(y)
+            .iter()
+            .flat_map(|c| [c.x, c.winding as f64, c.curve as f64, c.t])
+            .collect()
+    }
+
+    /// 扫描线 `y` 位于路径内部的区间，每 2 个数为一个区间 `[x1, x2]`，`fill_rule` 缺省时为非零规则
+    pub fn spans(&self, y: f64, fill_rule: Option<FillRule>) -> Vec<f64> {
+        self.scanline
+            .spans(y, fill_rule.unwrap_or_default())
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+/// 用平行线填充闭合路径内部，每 4 个数为一条线段 `[x1, y1, x2, y2]`，`angle` 为弧度，
+/// `spacing` 不是有限正数、路径坐标不是有限值或平行线超过 `MAX_HATCH_LINES` 条时返回空数组
+#[wasm_bindgen]
+pub fn rust_hatch_lines(
+    slice: &[f64],
+    spacing: f64,
+    angle: f64,
+    fill_rule: Option<FillRule>,
+) -> Vec<f64> {
+    let curves: Vec<[f64; 8]> = to_vec_of_arrays(slice);
+    hatch_lines(&curves, spacing, angle, fill_rule.unwrap_or_default())
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// 切割部分曲线[t1,t2]
+#[wasm_bindgen]
+pub fn rust_split_cubic_bezier_part(v: &[f64], t1: f64, t2: f64) -> Vec<f64> {
+    let mut v_part = [0.0; 8];
+    for (i, &value) in v.iter().enumerate() {
+        v_part[i] = value;
+    }
+    if t1 > 0.0 {
+        v_part = split_cubic_bezier(&v_part, t1).1;
+    }
+    if t2 < 1.0 {
+        let t = (t2 - t1) / (1.0 - t1);
+        v_part = split_cubic_bezier(&v_part, t).0;
+    }
+    v_part.to_vec()
+}
+
+fn flatten<T: Copy>(vec_of_arrays: Vec<[T; 8]>) -> Vec<T> {
+    let mut flattened_vec = Vec::with_capacity(vec_of_arrays.len() * 8);
+    for array in vec_of_arrays {
+        flattened_vec.extend_from_slice(&array);
+    }
+    flattened_vec
+}
+
+fn to_vec_of_arrays<T: Copy, const N: usize>(slice: &[T]) -> Vec<[T; N]> {
+    slice
+        .chunks_exact(N)
+        .map(|chunk| {
+            let array: [T; N] = chunk.try_into().unwrap();
+            array
+        })
+        .collect()
+}
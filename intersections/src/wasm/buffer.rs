@@ -0,0 +1,168 @@
+use alloc::vec::Vec;
+use js_sys::{Float64Array, Uint32Array};
+use wasm_bindgen::prelude::*;
+
+use crate::{get_intersections_with_stats, Curve, EndpointPolicy, Stats};
+
+/// 按列存放的相交点结果，可以跨调用复用
+///
+/// JS 通过 `t1`、`t2`、`x`、`y`、`curve1`、`curve2` 直接读取 wasm 内存中的数据，不做拷贝。
+/// 视图在下一次求交或 wasm 内存增长后失效，需要重新获取
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct IntersectionBuffer {
+    curves1: Vec<Curve>,
+    curves2: Vec<Curve>,
+    locations: Vec<[f64; 8]>,
+    t1: Vec<f64>,
+    t2: Vec<f64>,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    curve1: Vec<u32>,
+    curve2: Vec<u32>,
+    stats: Stats,
+}
+
+/// `IntersectionBuffer` 中按列存放的相交点
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocationColumns<'a> {
+    pub t1: &'a [f64],
+    pub t2: &'a [f64],
+    pub x: &'a [f64],
+    pub y: &'a [f64],
+    pub curve1: &'a [u32],
+    pub curve2: &'a [u32],
+}
+
+#[wasm_bindgen]
+impl IntersectionBuffer {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 相交点数量
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.t1.len()
+    }
+
+    /// 寻找两条路径的相交点，覆盖上一次的结果
+    pub fn intersect(
+        &mut self,
+        slice1: &[f64],
+        slice2: &[f64],
+        endpoint_policy: Option<EndpointPolicy>,
+    ) {
+        fill_curves(&mut self.curves1, slice1);
+        fill_curves(&mut self.curves2, slice2);
+        self.locations.clear();
+        self.stats = get_intersections_with_stats(
+            &self.curves1,
+            &self.curves2,
+            false,
+            endpoint_policy.unwrap_or_default(),
+            &mut self.locations,
+        );
+        self.fill_columns();
+    }
+
+    /// 寻找当前路径的相交点，覆盖上一次的结果
+    pub fn intersect_self(&mut self, slice: &[f64], endpoint_policy: Option<EndpointPolicy>) {
+        fill_curves(&mut self.curves1, slice);
+        self.locations.clear();
+        self.stats = get_intersections_with_stats(
+            &self.curves1,
+            &self.curves1,
+            true,
+            endpoint_policy.unwrap_or_default(),
+            &mut self.locations,
+        );
+        self.fill_columns();
+    }
+
+    /// 上一次求交的统计数据
+    #[wasm_bindgen(getter)]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// 第一条曲线上的参数
+    #[wasm_bindgen(getter)]
+    pub fn t1(&self) -> Float64Array {
+        // SAFETY: 视图只在缓冲区下一次修改前使用
+        unsafe { Float64Array::view(&self.t1) }
+    }
+
+    /// 第二条曲线上的参数
+    #[wasm_bindgen(getter)]
+    pub fn t2(&self) -> Float64Array {
+        unsafe { Float64Array::view(&self.t2) }
+    }
+
+    /// 相交点 x 坐标
+    #[wasm_bindgen(getter)]
+    pub fn x(&self) -> Float64Array {
+        unsafe { Float64Array::view(&self.x) }
+    }
+
+    /// 相交点 y 坐标
+    #[wasm_bindgen(getter)]
+    pub fn y(&self) -> Float64Array {
+        unsafe { Float64Array::view(&self.y) }
+    }
+
+    /// 第一条曲线的序号
+    #[wasm_bindgen(getter)]
+    pub fn curve1(&self) -> Uint32Array {
+        unsafe { Uint32Array::view(&self.curve1) }
+    }
+
+    /// 第二条曲线的序号
+    #[wasm_bindgen(getter)]
+    pub fn curve2(&self) -> Uint32Array {
+        unsafe { Uint32Array::view(&self.curve2) }
+    }
+}
+
+impl IntersectionBuffer {
+    /// 各列数据的切片
+    pub fn columns(&self) -> LocationColumns<'_> {
+        LocationColumns {
+            t1: &self.t1,
+            t2: &self.t2,
+            x: &self.x,
+            y: &self.y,
+            curve1: &self.curve1,
+            curve2: &self.curve2,
+        }
+    }
+
+    /// 将相交点拆分到各列，复用已分配的空间
+    fn fill_columns(&mut self) {
+        self.t1.clear();
+        self.t2.clear();
+        self.x.clear();
+        self.y.clear();
+        self.curve1.clear();
+        self.curve2.clear();
+        for &[t1, i1, x1, y1, t2, i2, _, _] in &self.locations {
+            self.t1.push(t1);
+            self.t2.push(t2);
+            self.x.push(x1);
+            self.y.push(y1);
+            self.curve1.push(i1 as u32);
+            self.curve2.push(i2 as u32);
+        }
+    }
+}
+
+/// 将扁平的坐标数组转换为曲线，复用已分配的空间
+fn fill_curves(curves: &mut Vec<Curve>, slice: &[f64]) {
+    curves.clear();
+    curves.extend(
+        slice
+            .chunks_exact(8)
+            .map(|chunk| -> Curve { chunk.try_into().unwrap() }),
+    );
+}
@@ -0,0 +1,102 @@
+/* C 接口测试：make -C intersections/tests/c */
+#include <math.h>
+#include <stdio.h>
+
+#include "intersections.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                        \
+  do {                                                                     \
+    if (!(cond)) {                                                         \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+      failures++;                                                          \
+    }                                                                      \
+  } while (0)
+
+static const double LINES[5][8] = {
+    {38.5, 0.0, 38.5, 0.0, 62.0, 87.0, 62.0, 87.0},
+    {0.0, 64.5, 0.0, 64.5, 80.0, 17.5, 80.0, 17.5},
+    {80.0, 17.5, 80.0, 17.5, 0.0, 17.5, 0.0, 17.5},
+    {0.0, 17.5, 0.0, 17.5, 78.5, 67.0, 78.5, 67.0},
+    {78.5, 67.0, 78.5, 67.0, 0.0, 64.5, 0.0, 64.5},
+};
+
+static void test_get(void) {
+  IntersectionsLocations locations;
+  CHECK(intersections_get(&LINES[0][0], 5, NULL, 0, true, INTERSECTIONS_EXCLUDE_SHARED,
+                          &locations) == INTERSECTIONS_OK);
+  CHECK(locations.len == 5);
+  for (size_t i = 0; i < locations.len; i++) {
+    const double *row = locations.data + i * 8;
+    CHECK(row[0] >= 0.0 && row[0] <= 1.0);
+    CHECK(row[1] < row[5]);
+    CHECK(fabs(row[2] - row[6]) < 1e-9 && fabs(row[3] - row[7]) < 1e-9);
+  }
+
+  /* 调用方提供的缓冲区，容量不足时只写入一部分 */
+  double out[2 * 8];
+  size_t total = 0;
+  CHECK(intersections_get_into(&LINES[0][0], 5, NULL, 0, true, INTERSECTIONS_EXCLUDE_SHARED,
+                               out, 2, &total) == INTERSECTIONS_OK);
+  CHECK(total == locations.len);
+  for (size_t i = 0; i < 2 * 8; i++) {
+    CHECK(out[i] == locations.data[i]);
+  }
+  intersections_locations_free(locations);
+
+  /* 相邻直线首尾相连，保留所有端点接触时多出连接点 */
+  IntersectionsLocations all;
+  CHECK(intersections_get(&LINES[0][0], 5, NULL, 0, true, INTERSECTIONS_INCLUDE_ALL, &all) ==
+        INTERSECTIONS_OK);
+  CHECK(all.len > total);
+  intersections_locations_free(all);
+
+  /* 未知的端点策略返回错误 */
+  IntersectionsLocations invalid;
+  CHECK(intersections_get(&LINES[0][0], 5, NULL, 0, true, 3, &invalid) ==
+        INTERSECTIONS_ERROR_INVALID_POLICY);
+  CHECK(invalid.data == NULL && invalid.len == 0);
+  CHECK(intersections_get_into(&LINES[0][0], 5, NULL, 0, true, 3, out, 2, &total) ==
+        INTERSECTIONS_ERROR_INVALID_POLICY);
+  CHECK(total == 0);
+}
+
+static void test_split_and_evaluate(void) {
+  const double curve[8] = {0.0, 0.0, 0.0, 100.0, 100.0, 100.0, 100.0, 0.0};
+  double part[8];
+  intersections_split_cubic_bezier_part(curve, 0.0, 0.5, part);
+  double point[2];
+  CHECK(intersections_evaluate(curve, 0.5, 0, point));
+  CHECK(fabs(part[6] - point[0]) < 1e-12 && fabs(part[7] - point[1]) < 1e-12);
+  CHECK(fabs(point[0] - 50.0) < 1e-12 && fabs(point[1] - 75.0) < 1e-12);
+  CHECK(!intersections_evaluate(curve, 1.5, 0, point));
+}
+
+static void test_collisions(void) {
+  IntersectionsCollisions collisions =
+      intersections_find_curve_bounds_collisions(&LINES[0][0], 5, NULL, 0, true, 1e-7);
+  CHECK(collisions.offsets_len == 6);
+  CHECK(collisions.offsets[5] == collisions.indices_len);
+  for (uint32_t i = 0; i < 5; i++) {
+    /* 每条曲线都和自己的边界碰撞 */
+    int has_self = 0;
+    for (uint32_t k = collisions.offsets[i]; k < collisions.offsets[i + 1]; k++) {
+      has_self |= collisions.indices[k] == i;
+    }
+    CHECK(has_self);
+  }
+  intersections_collisions_free(collisions);
+}
+
+int main(void) {
+  test_get();
+  test_split_and_evaluate();
+  test_collisions();
+  if (failures > 0) {
+    fprintf(stderr, "%d check(s) failed\n", failures);
+    return 1;
+  }
+  printf("ffi tests passed\n");
+  return 0;
+}
@@ -1,5 +1,3 @@
-use intersections;
-
 /// 数组每一项保留两位小数
 fn format_floats_to_two_decimals(floats: &[f64]) -> Vec<String> {
     floats.iter().map(|&num| format!("{:.2}", num)).collect()
@@ -11,8 +9,8 @@ fn arrays_equal<T: PartialEq>(arr1: &[T], arr2: &[T]) -> bool {
 }
 /// 判断float数组每一项是否相等
 fn float_arrays_equal(_arr1: &[f64], _arr2: &[f64]) -> bool {
-    let arr1 = format_floats_to_two_decimals(&_arr1);
-    let arr2 = format_floats_to_two_decimals(&_arr2);
+    let arr1 = format_floats_to_two_decimals(_arr1);
+    let arr2 = format_floats_to_two_decimals(_arr2);
     arrays_equal(&arr1, &arr2)
 }
 
@@ -111,7 +109,7 @@ fn test_evaluate() {
 /// 测试直线相交
 #[test]
 fn test_line_intersection() {
-    // 有相交点，并且t值为-1
+    // 有相交点，每一项为 [t1, i1, x1, y1, t2, i2, x2, y2]
     let curves1 = vec![
         [38.5, 0.0, 38.5, 0.0, 62.0, 87.0, 62.0, 87.0],
         [0.0, 64.5, 0.0, 64.5, 80.0, 17.5, 80.0, 17.5],
@@ -120,37 +118,54 @@ fn test_line_intersection() {
         [78.5, 67.0, 78.5, 67.0, 0.0, 64.5, 0.0, 64.5],
     ];
     let curves2 = curves1.clone();
-    const TEST_RES: [[f64; 6]; 5] = [
+    const TEST_RES: [[f64; 8]; 5] = [
         [
-            -1.0,
+            0.41546283092566183,
+            0.0,
             48.26337652675305,
             36.14526629053258,
-            -1.0,
+            0.6032922065844131,
+            1.0,
             48.26337652675305,
             36.14526629053258,
         ],
-        [-1.0, 43.22701149425287, 17.5, -1.0, 43.22701149425287, 17.5],
         [
-            -1.0,
+            0.20114942528735633,
+            0.0,
+            43.22701149425287,
+            17.5,
+            0.4596623563218391,
+            2.0,
+            43.22701149425287,
+            17.5,
+        ],
+        [
+            0.5787778513125965,
+            0.0,
             52.101279505846016,
             50.353673064195895,
-            -1.0,
+            0.6637105669534524,
+            3.0,
             52.101279505846016,
             50.353673064195895,
         ],
         [
-            -1.0,
+            0.7620278403426504,
+            0.0,
             56.40765424805228,
             66.29642210981058,
-            -1.0,
+            0.28143115607576713,
+            4.0,
             56.40765424805228,
             66.29642210981058,
         ],
         [
-            -1.0,
+            0.4823191058239101,
+            1.0,
             38.58552846591281,
             41.831002026276224,
-            -1.0,
+            0.49153539447022687,
+            3.0,
             38.58552846591281,
             41.831002026276224,
         ],
@@ -275,17 +290,8 @@ fn test_bezier_intersections() {
 
 #[test]
 fn test_line_intersections() {
-    let curves1:Vec<[f64; 8]> = vec![
-        [
-            0.0,
-            0.0,
-            0.0,
-            0.0,
-            109.5,
-            0.0,
-            109.5,
-            0.0
-        ],
+    let curves1: Vec<[f64; 8]> = vec![
+        [0.0, 0.0, 0.0, 0.0, 109.5, 0.0, 109.5, 0.0],
         [
             109.5,
             0.0,
@@ -294,7 +300,7 @@ fn test_line_intersections() {
             74.89366432468393,
             53.59184346845434,
             41.5,
-            49.5
+            49.5,
         ],
         [
             41.5,
@@ -304,18 +310,9 @@ fn test_line_intersections() {
             0.0,
             0.0,
             0.0,
-            0.0
-        ],
-        [
-            41.5,
-            0.0,
-            41.5,
-            0.0,
-            151.0,
             0.0,
-            151.0,
-            0.0
         ],
+        [41.5, 0.0, 41.5, 0.0, 151.0, 0.0, 151.0, 0.0],
         [
             151.0,
             0.0,
@@ -324,7 +321,7 @@ fn test_line_intersections() {
             116.39366432468393,
             53.59184346845434,
             83.0,
-            49.5
+            49.5,
         ],
         [
             83.0,
@@ -334,10 +331,1293 @@ fn test_line_intersections() {
             41.5,
             0.0,
             41.5,
-            0.0
-        ]
+            0.0,
+        ],
     ];
     let curves2 = curves1.clone();
     let mut locations = vec![];
     intersections::get_intersections(&curves1, &curves2, true, &mut locations);
 }
+
+/// 测试f32精度求交
+#[test]
+fn test_f32_intersections() {
+    let curves: Vec<[f64; 8]> = vec![
+        [100.0, 50.0, 100.0, 22.385763, 22.385763, 100.0, 50.0, 100.0],
+        [100.0, 50.0, 100.0, 22.385763, 0.0, 22.385763, 0.0, 50.0],
+        [100.0, 50.0, 100.0, 77.614237, 22.385763, 0.0, 50.0, 0.0],
+        [50.0, 100.0, 77.614237, 100.0, 0.0, 22.385763, 0.0, 50.0],
+        [50.0, 100.0, 22.385763, 100.0, 77.614237, 0.0, 50.0, 0.0],
+        [0.0, 50.0, 0.0, 77.614237, 77.614237, 0.0, 50.0, 0.0],
+    ];
+    let curves_f32: Vec<[f32; 8]> = curves.iter().map(|c| c.map(|v| v as f32)).collect();
+    let mut locations = vec![];
+    intersections::get_intersections(&curves, &curves, true, &mut locations);
+    let mut locations_f32 = vec![];
+    intersections::get_intersections(&curves_f32, &curves_f32, true, &mut locations_f32);
+    assert_eq!(locations_f32.len(), locations.len());
+    for (a, b) in locations.iter().zip(&locations_f32) {
+        for (&x, &y) in a.iter().zip(b) {
+            assert!((x - y as f64).abs() < 1e-2);
+        }
+    }
+}
+
+/// 测试双倍精度求交，远离原点的浅角度相交 f64 精度不足
+#[test]
+fn test_double_double_intersections() {
+    use intersections::{DoubleDouble, Float};
+
+    let tiny = DoubleDouble::from(1e-20);
+    assert_eq!(
+        ((DoubleDouble::ONE + tiny) - DoubleDouble::ONE).to_f64(),
+        1e-20
+    );
+
+    let off = 1e8;
+    let a = [
+        off,
+        off,
+        off + 33.0,
+        off + 30.0,
+        off + 66.0,
+        off + 30.0,
+        off + 100.0,
+        off,
+    ];
+    let b = [
+        off,
+        off + 21.5,
+        off + 33.0,
+        off + 21.0,
+        off + 66.0,
+        off + 22.0,
+        off + 100.0,
+        off + 21.5,
+    ];
+    let mut locations = vec![];
+    intersections::get_intersections(&[a], &[b], false, &mut locations);
+    assert!(locations.len() < 2);
+
+    let mut locations = vec![];
+    intersections::get_intersections(
+        &[a.map(DoubleDouble::from)],
+        &[b.map(DoubleDouble::from)],
+        false,
+        &mut locations,
+    );
+    assert_eq!(locations.len(), 2);
+    let t: Vec<f64> = locations.iter().map(|l| l[0].to_f64()).collect();
+    assert!(float_arrays_equal(
+        &t,
+        &[0.39054911479522936, 0.6014899365413523]
+    ));
+}
+
+/// 测试牛顿迭代修正相交点
+#[test]
+fn test_refined_intersections() {
+    let curves: Vec<[f64; 8]> = vec![
+        [100.0, 50.0, 100.0, 22.385763, 22.385763, 100.0, 50.0, 100.0],
+        [100.0, 50.0, 100.0, 22.385763, 0.0, 22.385763, 0.0, 50.0],
+        [100.0, 50.0, 100.0, 77.614237, 22.385763, 0.0, 50.0, 0.0],
+        [50.0, 100.0, 77.614237, 100.0, 0.0, 22.385763, 0.0, 50.0],
+        [50.0, 100.0, 22.385763, 100.0, 77.614237, 0.0, 50.0, 0.0],
+        [0.0, 50.0, 0.0, 77.614237, 77.614237, 0.0, 50.0, 0.0],
+    ];
+    let mut locations = vec![];
+    intersections::get_intersections(&curves, &curves, true, &mut locations);
+    let refined =
+        intersections::get_refined_intersections(&curves, &curves, true, Default::default(), 1e-12);
+    assert_eq!(refined.len(), locations.len());
+    for (location, item) in locations.iter().zip(&refined) {
+        let residual =
+            ((location[2] - location[6]).powi(2) + (location[3] - location[7]).powi(2)).sqrt();
+        assert!(item.residual <= residual);
+        assert!(item.residual < 1e-12);
+        assert!(item.error_bound < 1e-12);
+        assert_eq!(item.status, intersections::RefineStatus::Converged);
+        assert!(float_arrays_equal(location, &item.location));
+    }
+
+    // 不做迭代时误差上界覆盖初值到真实交点 (0.5, 0.5) 的距离
+    let h = [0.0, 0.0, 10.0 / 3.0, 0.0, 20.0 / 3.0, 0.0, 10.0, 0.0];
+    let v = [5.0, -5.0, 5.0, -5.0 / 3.0, 5.0, 5.0 / 3.0, 5.0, 5.0];
+    let refined = intersections::refine_intersection(&h, &v, 0.52, 0.47, f64::INFINITY);
+    assert_eq!(refined.iterations, 0);
+    let error = (0.02f64).hypot(0.03);
+    assert!(refined.error_bound >= error - 1e-12 && refined.error_bound < 2.0 * error);
+
+    // 切线相交时雅可比矩阵奇异，保留初值并标记出来
+    let v1 = [0.0, 0.0, 30.0, 40.0, 70.0, 40.0, 100.0, 0.0];
+    let v2 = [0.0, 30.0, 30.0, 30.0, 70.0, 30.0, 100.0, 30.0];
+    let refined = intersections::refine_intersection(&v1, &v2, 0.5, 0.5, 1e-12);
+    assert_eq!(refined.iterations, 0);
+    assert_eq!((refined.t1, refined.t2), (0.5, 0.5));
+    assert!(refined.residual < 1e-12);
+    assert_eq!(refined.status, intersections::RefineStatus::Singular);
+    assert_eq!(refined.error_bound, f64::INFINITY);
+
+    // 直线的延长线与另一条曲线相交，参数限制在 [0, 1] 内时残差无法达到要求
+    let v1 = [0.0, 0.0, 3.0, 0.0, 7.0, 0.0, 10.0, 0.0];
+    let v2 = [20.0, -5.0, 20.0, -2.0, 20.0, 2.0, 20.0, 5.0];
+    let refined = intersections::refine_intersection(&v1, &v2, 0.5, 0.5, 1e-12);
+    assert_eq!(refined.t1, 1.0);
+    assert_eq!(refined.status, intersections::RefineStatus::Stalled);
+}
+
+/// 测试交换输入顺序时结果对称
+#[test]
+fn test_symmetric_intersections() {
+    let curves1: Vec<[f64; 8]> = vec![
+        [100.0, 50.0, 100.0, 22.385763, 22.385763, 100.0, 50.0, 100.0],
+        [100.0, 50.0, 100.0, 22.385763, 0.0, 22.385763, 0.0, 50.0],
+        [18.0, 251.5, 18.0, 251.5, 227.0, 90.0, 227.0, 90.0],
+    ];
+    let curves2: Vec<[f64; 8]> = vec![
+        [50.0, 100.0, 77.614237, 100.0, 0.0, 22.385763, 0.0, 50.0],
+        [0.0, 50.0, 0.0, 77.614237, 77.614237, 0.0, 50.0, 0.0],
+        [
+            1.0, 131.842, 72.3227, 8.75809, 110.937, -81.0265, 203.5, 117.342,
+        ],
+    ];
+    let mut forward = vec![];
+    intersections::get_intersections(&curves1, &curves2, false, &mut forward);
+    let mut backward = vec![];
+    intersections::get_intersections(&curves2, &curves1, false, &mut backward);
+    let mut swapped: Vec<[f64; 8]> = backward
+        .iter()
+        .map(|&[t1, i1, x1, y1, t2, i2, x2, y2]| [t2, i2, x2, y2, t1, i1, x1, y1])
+        .collect();
+    intersections::sort_locations(&mut swapped);
+    assert!(!forward.is_empty());
+    assert_eq!(forward, swapped);
+}
+
+/// 测试输入曲线重排后结果不变
+#[test]
+fn test_permuted_intersections() {
+    let curves: Vec<[f64; 8]> = vec![
+        [100.0, 50.0, 100.0, 22.385763, 22.385763, 100.0, 50.0, 100.0],
+        [100.0, 50.0, 100.0, 22.385763, 0.0, 22.385763, 0.0, 50.0],
+        [100.0, 50.0, 100.0, 77.614237, 22.385763, 0.0, 50.0, 0.0],
+        [50.0, 100.0, 77.614237, 100.0, 0.0, 22.385763, 0.0, 50.0],
+        [50.0, 100.0, 22.385763, 100.0, 77.614237, 0.0, 50.0, 0.0],
+        [0.0, 50.0, 0.0, 77.614237, 77.614237, 0.0, 50.0, 0.0],
+    ];
+    let order = [3, 5, 0, 4, 1, 2];
+    let permuted: Vec<[f64; 8]> = order.iter().map(|&i| curves[i]).collect();
+    let mut expected = vec![];
+    intersections::get_intersections(&curves, &curves, true, &mut expected);
+    let mut locations = vec![];
+    intersections::get_intersections(&permuted, &permuted, true, &mut locations);
+    let mut remapped: Vec<[f64; 8]> = locations
+        .iter()
+        .map(|location| {
+            let mut location = *location;
+            location[1] = order[location[1] as usize] as f64;
+            location[5] = order[location[5] as usize] as f64;
+            if location[1] > location[5] {
+                let [t1, i1, x1, y1, t2, i2, x2, y2] = location;
+                location = [t2, i2, x2, y2, t1, i1, x1, y1];
+            }
+            location
+        })
+        .collect();
+    intersections::sort_locations(&mut remapped);
+    assert_eq!(expected, remapped);
+}
+
+/// 测试手柄在弦上的曲线按直线求交，参数映射回曲线参数
+#[test]
+fn test_straight_curve_intersections() {
+    let curves1: Vec<[f64; 8]> = vec![
+        // 手柄位于 1/3、2/3 处的直线
+        [0.0, 0.0, 20.0, 0.0, 40.0, 0.0, 60.0, 0.0],
+        // 手柄不均匀的直线
+        [0.0, 10.0, 5.0, 10.0, 30.0, 10.0, 60.0, 10.0],
+    ];
+    let curves2: Vec<[f64; 8]> = vec![
+        [15.0, -5.0, 15.0, -5.0, 15.0, 20.0, 15.0, 20.0],
+        // 与第一条直线重合的部分
+        [30.0, 0.0, 40.0, 0.0, 60.0, 0.0, 90.0, 0.0],
+    ];
+    let mut locations = vec![];
+    intersections::get_intersections(&curves1, &curves2, false, &mut locations);
+    assert_eq!(locations.len(), 4);
+    let curves = [curves1, curves2];
+    for location in &locations {
+        for (k, curve) in [(0, 0), (4, 1)] {
+            let v = &curves[curve][location[k + 1] as usize];
+            // 控制点与端点重合的直线沿用弦上的参数
+            if v[0] == v[2] && v[1] == v[3] && v[4] == v[6] && v[5] == v[7] {
+                continue;
+            }
+            let point = intersections::evaluate(v, location[k], 0).unwrap();
+            assert!((point[0] - location[k + 2]).abs() < 1e-9);
+            assert!((point[1] - location[k + 3]).abs() < 1e-9);
+        }
+    }
+    // 均匀手柄的参数化是线性的
+    assert!((locations[0][0] - 0.25).abs() < 1e-12);
+    // 重合部分取端点
+    assert_eq!(locations[1][2], 30.0);
+    assert_eq!(locations[1][4], 0.0);
+    assert!((locations[1][0] - 0.5).abs() < 1e-12);
+}
+
+/// 测试端点接触的处理方式
+#[test]
+fn test_endpoint_policy() {
+    use intersections::EndpointPolicy;
+    let curves: Vec<[f64; 8]> = vec![
+        // 三角形路径，相邻曲线首尾相连
+        [0.0, 0.0, 0.0, 0.0, 100.0, 0.0, 100.0, 0.0],
+        [100.0, 0.0, 120.0, 30.0, 80.0, 70.0, 50.0, 100.0],
+        [50.0, 100.0, 50.0, 100.0, 0.0, 0.0, 0.0, 0.0],
+        // 端点落在第一条直线内部
+        [50.0, 0.0, 50.0, 0.0, 50.0, -50.0, 50.0, -50.0],
+        // 与三角形内部相交
+        [20.0, 20.0, 20.0, 20.0, 120.0, 60.0, 120.0, 60.0],
+    ];
+    let run = |policy| {
+        let mut locations = vec![];
+        intersections::get_intersections_with_policy(
+            &curves,
+            &curves,
+            true,
+            policy,
+            &mut locations,
+        );
+        locations
+    };
+    let include_all = run(EndpointPolicy::IncludeAll);
+    let exclude_shared = run(EndpointPolicy::ExcludeShared);
+    let exclude_all = run(EndpointPolicy::ExcludeAll);
+    let mut locations = vec![];
+    intersections::get_intersections(&curves, &curves, true, &mut locations);
+    assert_eq!(exclude_shared, locations);
+    // 三个连接点只在保留所有端点时出现
+    assert_eq!(include_all.len(), exclude_shared.len() + 3);
+    // T 形接触只在排除所有端点时去掉
+    assert_eq!(exclude_shared.len(), exclude_all.len() + 1);
+    assert!(exclude_all
+        .iter()
+        .all(|l| l[0] > 0.0 && l[0] < 1.0 && l[4] > 0.0 && l[4] < 1.0));
+    assert!(include_all
+        .iter()
+        .any(|l| l[1] == 1.0 && l[0] == 1.0 && l[5] == 2.0 && l[4] == 0.0));
+}
+
+/// 测试结构化的相交点
+#[test]
+fn test_intersection_objects() {
+    use intersections::{EndpointPolicy, Intersection, LocationKind};
+    let curves: Vec<[f64; 8]> = vec![
+        [0.0, 0.0, 0.0, 0.0, 100.0, 0.0, 100.0, 0.0],
+        [100.0, 0.0, 100.0, 0.0, 50.0, 0.0, 50.0, -50.0],
+        // 端点落在第一条直线内部
+        [20.0, 0.0, 20.0, 0.0, 20.0, 20.0, 20.0, 20.0],
+        [30.0, -20.0, 30.0, -20.0, 30.0, 20.0, 30.0, 20.0],
+    ];
+    let mut locations = vec![];
+    intersections::get_intersections_with_policy(
+        &curves,
+        &curves,
+        true,
+        EndpointPolicy::IncludeAll,
+        &mut locations,
+    );
+    let objects: Vec<Intersection> = locations
+        .iter()
+        .map(|location| Intersection::from_location(location, true))
+        .collect();
+    let kinds: Vec<(u32, u32, LocationKind)> = objects
+        .iter()
+        .map(|item| (item.curve1, item.curve2, item.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (0, 1, LocationKind::SharedEndpoint),
+            (0, 2, LocationKind::Endpoint),
+            (0, 3, LocationKind::Interior),
+        ]
+    );
+    assert_eq!(objects[2].point1, [30.0, 0.0]);
+    assert_eq!(objects[2].t1, locations[2][0]);
+    assert_eq!(objects[2].geometry, None);
+}
+
+/// 测试相交点处的切线、法线、曲率和交角
+#[test]
+fn test_location_geometry() {
+    use intersections::{Intersection, LocationGeometry};
+    use std::f64::consts::FRAC_PI_2;
+    let curves: Vec<[f64; 8]> = vec![
+        [0.0, 0.0, 0.0, 0.0, 100.0, 0.0, 100.0, 0.0],
+        // 端点处手柄长度为零，切线取两个手柄之间的方向
+        [100.0, 0.0, 100.0, 0.0, 50.0, 0.0, 50.0, -50.0],
+        [30.0, -20.0, 30.0, -20.0, 30.0, 20.0, 30.0, 20.0],
+        // 向上凸起的弧线，从右向左穿过第一条直线
+        [80.0, -10.0, 80.0, 40.0, 40.0, 40.0, 40.0, -10.0],
+    ];
+    let mut locations = vec![];
+    intersections::get_intersections(&curves, &curves, true, &mut locations);
+    let geometries = intersections::location_geometries(&curves, &curves, &locations);
+    let pairs: Vec<(f64, f64)> = locations.iter().map(|l| (l[1], l[5])).collect();
+    assert_eq!(pairs, vec![(0.0, 2.0), (0.0, 3.0), (0.0, 3.0), (1.0, 3.0)]);
+
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+    let g = geometries[0];
+    assert_eq!(
+        (g.tangent1, g.normal1, g.curvature1),
+        ([1.0, 0.0], [0.0, -1.0], 0.0)
+    );
+    assert_eq!(g.tangent2, [0.0, 1.0]);
+    assert!(close(g.angle, FRAC_PI_2));
+
+    // 弧线一直向左转，两个相交点处曲率都为正，交角符号相反
+    let (a, b) = (geometries[1], geometries[2]);
+    assert!(a.curvature2 > 0.0 && b.curvature2 > 0.0);
+    assert!(a.angle * b.angle < 0.0);
+    for g in &geometries {
+        for [x, y] in [g.tangent1, g.tangent2] {
+            assert!(close(x * x + y * y, 1.0));
+        }
+    }
+
+    let g = LocationGeometry::new(&curves[0], 1.0, &curves[1], 0.0);
+    assert_eq!(g.tangent2, [-1.0, 0.0]);
+    assert!(close(g.angle, std::f64::consts::PI));
+
+    let object = Intersection::from_location(&locations[0], true).with_geometry(&curves, &curves);
+    assert_eq!(object.geometry, Some(geometries[0]));
+    assert_eq!(g.to_array()[11], g.crossing.sign() as f64);
+}
+
+/// 测试相交点处的穿过方向
+#[test]
+fn test_crossing_direction() {
+    use intersections::{get_crossing, Crossing};
+    let line = [0.0, 0.0, 0.0, 0.0, 100.0, 0.0, 100.0, 0.0];
+    let reversed = [100.0, 0.0, 100.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+    let down = [50.0, -50.0, 50.0, -50.0, 50.0, 50.0, 50.0, 50.0];
+    // y 轴向下时，向右前进的左侧为上方
+    assert_eq!(get_crossing(&line, 0.5, &down, 0.5), Crossing::LeftToRight);
+    assert_eq!(get_crossing(&down, 0.5, &line, 0.5), Crossing::RightToLeft);
+    assert_eq!(
+        get_crossing(&reversed, 0.5, &down, 0.5),
+        Crossing::RightToLeft
+    );
+
+    // 在中点与直线相切，留在直线下方
+    let touch = [0.0, 12.0, 30.0, -4.0, 70.0, -4.0, 100.0, 12.0];
+    assert_eq!(get_crossing(&line, 0.5, &touch, 0.5), Crossing::Touch);
+    assert_eq!(get_crossing(&touch, 0.5, &line, 0.5), Crossing::Touch);
+    // 在拐点处与直线相切，从上方穿到下方
+    let inflection = [0.0, -10.0, 30.0, 10.0, 70.0, -10.0, 100.0, 10.0];
+    assert_eq!(
+        get_crossing(&line, 0.5, &inflection, 0.5),
+        Crossing::LeftToRight
+    );
+    assert_eq!(
+        get_crossing(&reversed, 0.5, &inflection, 0.5),
+        Crossing::RightToLeft
+    );
+    assert_eq!(
+        get_crossing(&inflection, 0.5, &line, 0.5),
+        Crossing::RightToLeft
+    );
+
+    // 交换两组曲线后方向相反，与裁剪时是否交换曲线无关
+    let curves1: Vec<[f64; 8]> = vec![
+        [0.0, 0.0, 0.0, 0.0, 100.0, 0.0, 100.0, 0.0],
+        [0.0, 80.0, 30.0, -20.0, 70.0, 120.0, 100.0, 20.0],
+    ];
+    let curves2: Vec<[f64; 8]> = vec![
+        [80.0, -10.0, 80.0, 40.0, 40.0, 40.0, 40.0, -10.0],
+        [10.0, 0.0, 40.0, 150.0, 60.0, -50.0, 90.0, 100.0],
+    ];
+    let (mut forward, mut backward) = (vec![], vec![]);
+    intersections::get_intersections(&curves1, &curves2, false, &mut forward);
+    intersections::get_intersections(&curves2, &curves1, false, &mut backward);
+    assert!(forward.len() > 2);
+    let crossings = intersections::location_crossings(&curves1, &curves2, &forward);
+    assert!(crossings.iter().all(|&c| c != Crossing::Touch));
+    for (location, crossing) in forward.iter().zip(&crossings) {
+        let other = backward
+            .iter()
+            .position(|l| {
+                l[1] == location[5]
+                    && l[5] == location[1]
+                    && (l[0] - location[4]).abs() < 1e-7
+                    && (l[4] - location[0]).abs() < 1e-7
+            })
+            .unwrap();
+        let reversed =
+            intersections::location_crossings(&curves2, &curves1, &backward[other..other + 1]);
+        assert_eq!(reversed[0], crossing.reversed());
+    }
+}
+
+/// 测试两条曲线及两条路径之间的最短距离
+#[test]
+fn test_min_distance() {
+    use intersections::{find_within_distance, min_distance, min_path_distance};
+    // 密集采样得到的近似最短距离
+    let brute_force = |v1: &[f64; 8], v2: &[f64; 8]| {
+        let n = 1000;
+        let points = |v: &[f64; 8]| -> Vec<[f64; 2]> {
+            (0..=n)
+                .map(|k| intersections::evaluate(v, k as f64 / n as f64, 0).unwrap())
+                .collect()
+        };
+        let (a, b) = (points(v1), points(v2));
+        a.iter()
+            .flat_map(|p| b.iter().map(move |q| (p[0] - q[0]).hypot(p[1] - q[1])))
+            .fold(f64::INFINITY, f64::min)
+    };
+
+    let line: [f64; 8] = [0.0, 0.0, 0.0, 0.0, 100.0, 0.0, 100.0, 0.0];
+    let arch = [0.0, 40.0, 30.0, 10.0, 70.0, 10.0, 100.0, 40.0];
+    let result = min_distance(&line, &arch);
+    assert!((result.distance - 17.5).abs() < 1e-6);
+    assert!((result.t2 - 0.5).abs() < 1e-4);
+    assert!((result.point1[0] - 50.0).abs() < 1e-2 && result.point1[1] == 0.0);
+
+    let curve1 = [10.0, 0.0, 40.0, 150.0, 60.0, -50.0, 90.0, 100.0];
+    let curve2 = [120.0, 20.0, 160.0, 90.0, 90.0, 120.0, 140.0, 160.0];
+    let result = min_distance(&curve1, &curve2);
+    let expected = brute_force(&curve1, &curve2);
+    assert!(result.distance <= expected + 1e-6 && result.distance > expected - 0.1);
+    let [x1, y1] = intersections::evaluate(&curve1, result.t1, 0).unwrap();
+    assert!((x1 - result.point1[0]).abs() < 1e-9 && (y1 - result.point1[1]).abs() < 1e-9);
+
+    // 相交的曲线距离为零
+    let crossing = [0.0, 80.0, 30.0, -20.0, 70.0, 120.0, 100.0, 20.0];
+    assert!(min_distance(&curve1, &crossing).distance < 1e-6);
+
+    // 路径之间的最短距离落在第二条路径的第二条曲线上
+    let path1 = vec![line, [100.0, 0.0, 100.0, 0.0, 100.0, 50.0, 100.0, 50.0]];
+    let path2 = vec![
+        [0.0, 60.0, 0.0, 60.0, 50.0, 60.0, 50.0, 60.0],
+        [50.0, 60.0, 50.0, 60.0, 130.0, 60.0, 130.0, 60.0],
+    ];
+    let result = min_path_distance(&path1, &path2).unwrap();
+    assert_eq!((result.curve1, result.curve2), (1, 1));
+    assert!((result.distance - 10.0).abs() < 1e-6);
+    assert_eq!(min_path_distance(&path1, &[]), None);
+
+    // 阈值查询
+    let found = find_within_distance(&path1, &path2, 10.5).unwrap().unwrap();
+    assert!(found.distance <= 10.5 && !found.truncated);
+    assert_eq!(find_within_distance(&path1, &path2, 9.5), Ok(None));
+
+    // 同心的圆弧处处距离约为 10，阈值略小于最短距离时片段对无法排除，搜索提前结束
+    let k = 0.5522847498;
+    let inner: [f64; 8] = [100.0, 0.0, 100.0, 100.0 * k, 100.0 * k, 100.0, 0.0, 100.0];
+    let outer = inner.map(|x| x * 1.1);
+    assert_eq!(
+        find_within_distance(&[inner; 30], &[outer; 30], 9.9999999),
+        Err(intersections::SearchTruncated)
+    );
+}
+
+/// 测试点到曲线的投影以及曲线之间的豪斯多夫距离和弗雷歇距离
+#[test]
+fn test_curve_similarity() {
+    use intersections::{
+        discrete_frechet_distance, frechet_distance, hausdorff_distance, project_point,
+    };
+    let line: [f64; 8] = [0.0, 0.0, 0.0, 0.0, 100.0, 0.0, 100.0, 0.0];
+    let arch: [f64; 8] = [0.0, 40.0, 30.0, 10.0, 70.0, 10.0, 100.0, 40.0];
+    let projection = project_point(&arch, [50.0, 30.0]);
+    assert!((projection.t - 0.5).abs() < 1e-6);
+    assert!((projection.distance - 12.5).abs() < 1e-9);
+    // 参数速度很大的曲线来回折返，均匀采样时最近的采样点落在另一段上
+    let folded: [f64; 8] = [0.0, 0.0, 1000.0, 0.0, -900.0, 10.0, 100.0, 10.0];
+    let projection = project_point(&folded, [57.583829940710075, 5.045581326140919]);
+    assert!((projection.distance - 0.129846).abs() < 1e-6);
+
+    // 弧线的两个端点离直线最远
+    let result = hausdorff_distance(&[line], &[arch], 0.01).unwrap();
+    assert!(result.distance <= 40.0 && result.distance > 39.99);
+    assert!(result.t2 == 0.0 || result.t2 == 1.0);
+    assert!((result.point1[1] - 0.0).abs() < 1e-9);
+    let distance = |p: [f64; 2], q: [f64; 2]| (p[0] - q[0]).hypot(p[1] - q[1]);
+    assert!((distance(result.point1, result.point2) - result.distance).abs() < 1e-9);
+
+    // 方向相反的同一条线段：豪斯多夫距离为零，弗雷歇距离为线段长度
+    let reversed = [100.0, 0.0, 100.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+    assert!(
+        hausdorff_distance(&[line], &[reversed], 0.1)
+            .unwrap()
+            .distance
+            < 1e-6
+    );
+    assert_eq!(frechet_distance(&[line], &[reversed], 1.0), Some(100.0));
+
+    // 平移后的路径，细分后的离散弗雷歇距离不小于连续弗雷歇距离
+    let offset: Vec<[f64; 8]> = vec![
+        [0.0, 10.0, 0.0, 10.0, 50.0, 10.0, 50.0, 10.0],
+        [50.0, 10.0, 50.0, 10.0, 100.0, 10.0, 100.0, 10.0],
+    ];
+    let frechet = frechet_distance(&[line], &offset, 0.5).unwrap();
+    assert!((10.0..10.5).contains(&frechet));
+    let result = hausdorff_distance(&[line], &offset, 0.1).unwrap();
+    assert!((result.distance - 10.0).abs() < 0.1);
+
+    assert_eq!(
+        discrete_frechet_distance(
+            &[[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]],
+            &[[0.0, 1.0], [2.0, 1.0]]
+        ),
+        Some(std::f64::consts::SQRT_2)
+    );
+    assert_eq!(discrete_frechet_distance::<f64>(&[], &[[0.0, 0.0]]), None);
+    assert_eq!(hausdorff_distance(&[line], &[], 1e-3), None);
+
+    // 精度不是有限正数时不做细分
+    for precision in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert_eq!(frechet_distance(&[line], &[arch], precision), None);
+        assert_eq!(hausdorff_distance(&[line], &[arch], precision), None);
+    }
+
+    // 细分后的点数超过上限时不再计算
+    assert!(frechet_distance(&[line], &[line], 0.1).is_some());
+    assert_eq!(frechet_distance(&[line], &[line], 0.01), None);
+    assert!(100.0 / 0.01 > intersections::MAX_FRECHET_POINTS as f64);
+}
+
+/// 测试任意边界框的碰撞以及点选、框选
+#[test]
+fn test_bounds_collisions() {
+    use intersections::{Broadphase, CollisionDetection, RectSelection};
+    let bounds: Vec<[f64; 4]> = vec![
+        [0.0, 0.0, 10.0, 10.0],
+        [5.0, 5.0, 15.0, 15.0],
+        [20.0, 0.0, 30.0, 10.0],
+        [10.5, 0.0, 12.0, 2.0],
+    ];
+
+    // 两组相同的边界框只记录一次
+    let collisions = CollisionDetection::find_bounds_collisions(
+        &bounds,
+        &bounds,
+        false,
+        0.0,
+        Broadphase::default(),
+    );
+    assert_eq!(collisions.len(), 4);
+    assert_eq!(collisions.offsets, vec![0, 2, 4, 5, 6]);
+    assert_eq!(collisions.get(1), &[0, 1]);
+    assert_eq!(collisions.get(2), &[2]);
+    // 两组相同时自身碰撞与容差无关，两组不同时按边界框判断
+    for broadphase in [Broadphase::SweepAndPrune, Broadphase::Bvh] {
+        let collisions =
+            CollisionDetection::find_bounds_collisions(&bounds, &bounds, false, -1.0, broadphase);
+        assert!((0..4).all(|i| collisions.get(i).contains(&(i as u32))));
+        let shrunk: Vec<[f64; 4]> = bounds
+            .iter()
+            .map(|b| [b[0], b[1], b[2], b[1] + 0.5])
+            .collect();
+        let collisions =
+            CollisionDetection::find_bounds_collisions(&bounds, &shrunk, false, -1.0, broadphase);
+        assert_eq!(collisions.get(3), &[] as &[u32]);
+    }
+
+    // 自身碰撞时碰撞对在两个方向上都记录，间隙在容差内也算碰撞
+    let collisions =
+        CollisionDetection::find_bounds_collisions(&bounds, &[], true, 1.0, Broadphase::default());
+    let pairs: Vec<(u32, u32)> = collisions.pairs().collect();
+    assert_eq!(
+        pairs,
+        vec![
+            (0, 0),
+            (0, 1),
+            (0, 3),
+            (1, 0),
+            (1, 1),
+            (2, 2),
+            (3, 0),
+            (3, 3)
+        ]
+    );
+
+    // 坐标含 NaN 的边界框不与任何边界框碰撞
+    let with_nan = [[0.0, 0.0, 10.0, 10.0], [f64::NAN, 0.0, 10.0, 10.0]];
+    let collisions = CollisionDetection::find_bounds_collisions(
+        &with_nan,
+        &with_nan,
+        true,
+        0.0,
+        Broadphase::default(),
+    );
+    assert_eq!(collisions.offsets, vec![0, 1, 1]);
+
+    assert_eq!(
+        CollisionDetection::find_bounds_at_point(&bounds, [7.0, 7.0], 0.0),
+        vec![0, 1]
+    );
+    assert_eq!(
+        CollisionDetection::find_bounds_at_point(&bounds, [10.2, 1.0], 0.0),
+        Vec::<u32>::new()
+    );
+    assert_eq!(
+        CollisionDetection::find_bounds_at_point(&bounds, [10.2, 1.0], 0.5),
+        vec![0, 3]
+    );
+    let rect = [-1.0, -1.0, 12.0, 12.0];
+    assert_eq!(
+        CollisionDetection::find_bounds_in_rect(&bounds, rect, RectSelection::Intersects),
+        vec![0, 1, 3]
+    );
+    assert_eq!(
+        CollisionDetection::find_bounds_in_rect(&bounds, rect, RectSelection::Contains),
+        vec![0, 3]
+    );
+}
+
+/// 测试层次包围盒与扫描的结果相同，包括只在一个方向上聚集的分布
+#[test]
+fn test_bvh_broadphase() {
+    use intersections::{Broadphase, Bvh, CollisionDetection};
+    let mut seed = 1u32;
+    let mut random = |scale: f64| {
+        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (seed >> 8) as f64 / (1u32 << 24) as f64 * scale
+    };
+    let mut column = vec![];
+    let mut l_shape = vec![];
+    let mut scattered = vec![];
+    for i in 0..300 {
+        let (x, y) = (random(4.0), i as f64 * 3.0 + random(2.0));
+        column.push([x, y, x + 5.0, y + 4.0]);
+        l_shape.push(if i % 2 == 0 {
+            [x, y, x + 5.0, y + 4.0]
+        } else {
+            [y, x, y + 4.0, x + 5.0]
+        });
+        let (x, y, w, h) = (random(500.0), random(500.0), random(40.0), random(40.0));
+        scattered.push([x, y, x + w, y + h]);
+    }
+    let mut nan = scattered.clone();
+    nan[7][2] = f64::NAN;
+
+    for (bounds_a, bounds_b) in [
+        (&column, &column),
+        (&l_shape, &l_shape),
+        (&scattered, &column),
+        (&nan, &scattered),
+    ] {
+        for is_self in [true, false] {
+            let sweep = CollisionDetection::find_bounds_collisions(
+                bounds_a,
+                bounds_b,
+                is_self,
+                0.5,
+                Broadphase::SweepAndPrune,
+            );
+            let bvh = CollisionDetection::find_bounds_collisions(
+                bounds_a,
+                bounds_b,
+                is_self,
+                0.5,
+                Broadphase::Bvh,
+            );
+            assert_eq!(sweep, bvh);
+            assert!(!sweep.indices.is_empty());
+        }
+    }
+
+    // 直接查询
+    let bvh = Bvh::new(&nan);
+    assert_eq!(bvh.len(), 300);
+    let mut found = vec![];
+    bvh.query([0.0, 0.0, 100.0, 100.0], 0.0, |i| found.push(i));
+    found.sort_unstable();
+    let expected: Vec<u32> = (0..300)
+        .filter(|&i| {
+            let b: [f64; 4] = nan[i as usize];
+            b[0] <= 100.0 && b[2] >= 0.0 && b[1] <= 100.0 && b[3] >= 0.0
+        })
+        .collect();
+    assert_eq!(found, expected);
+    let mut count = 0;
+    Bvh::<f64>::new(&[]).query([0.0, 0.0, 1.0, 1.0], 0.0, |_| count += 1);
+    assert_eq!(count, 0);
+}
+
+/// 测试把路径裁剪到矩形和网格
+#[test]
+fn test_clip_to_rect() {
+    use intersections::{clip_to_grid, clip_to_rect, TileGrid};
+    let line = |x0: f64, y0: f64, x1: f64, y1: f64| [x0, y0, x0, y0, x1, y1, x1, y1];
+    let polygon = |points: &[[f64; 2]]| -> Vec<[f64; 8]> {
+        (0..points.len())
+            .map(|i| {
+                let (p, q) = (points[i], points[(i + 1) % points.len()]);
+                line(p[0], p[1], q[0], q[1])
+            })
+            .collect()
+    };
+    // 闭合且只由直线组成的路径的面积
+    let area = |path: &[[f64; 8]]| -> f64 {
+        for (i, v) in path.iter().enumerate() {
+            let next = path[(i + 1) % path.len()];
+            assert_eq!((v[6], v[7]), (next[0], next[1]));
+        }
+        path.iter().map(|v| v[0] * v[7] - v[6] * v[1]).sum::<f64>() / 2.0
+    };
+    let rect = [0.0, 0.0, 10.0, 10.0];
+
+    // 开放路径在边界处精确切开
+    let paths = clip_to_rect(&[line(-10.0, 5.0, 20.0, 5.0)], rect, false);
+    assert_eq!(paths.len(), 1);
+    assert_eq!((paths[0][0][0], paths[0][0][6]), (0.0, 10.0));
+    let arch: [f64; 8] = [1.0, 5.0, 3.0, -5.0, 7.0, -5.0, 9.0, 5.0];
+    let paths = clip_to_rect(&[arch], rect, false);
+    assert_eq!(paths.len(), 2);
+    assert_eq!((paths[0][0][0], paths[0][0][7]), (1.0, 0.0));
+    assert_eq!((paths[1][0][1], paths[1][0][6]), (0.0, 9.0));
+
+    // 闭合路径沿边界补齐
+    let square = polygon(&[[-5.0, -5.0], [5.0, -5.0], [5.0, 5.0], [-5.0, 5.0]]);
+    let paths = clip_to_rect(&square, rect, true);
+    assert_eq!(paths.len(), 1);
+    assert!((area(&paths[0]) - 25.0).abs() < 1e-9);
+    let around = polygon(&[[-50.0, -50.0], [50.0, -50.0], [50.0, 50.0], [-50.0, 50.0]]);
+    assert!((area(&clip_to_rect(&around, rect, true)[0]) - 100.0).abs() < 1e-9);
+    let outside = polygon(&[[20.0, 0.0], [30.0, 0.0], [30.0, 10.0]]);
+    assert!(clip_to_rect(&outside, rect, true).is_empty());
+    assert!(clip_to_rect(&outside, rect, false).is_empty());
+
+    // 曲线组成的圆，裁剪后仍然闭合且在矩形内
+    let circle: Vec<[f64; 8]> = vec![
+        [10.0, 5.0, 10.0, 7.76, 7.76, 10.0, 5.0, 10.0],
+        [5.0, 10.0, 2.24, 10.0, 0.0, 7.76, 0.0, 5.0],
+        [0.0, 5.0, 0.0, 2.24, 2.24, 0.0, 5.0, 0.0],
+        [5.0, 0.0, 7.76, 0.0, 10.0, 2.24, 10.0, 5.0],
+    ];
+    let paths = clip_to_rect(&circle, [2.0, 2.0, 20.0, 20.0], true);
+    assert_eq!(paths.len(), 1);
+    let path = &paths[0];
+    for (i, v) in path.iter().enumerate() {
+        let next = path[(i + 1) % path.len()];
+        assert!((v[6] - next[0]).hypot(v[7] - next[1]) < 1e-9);
+        for k in 0..4 {
+            assert!(v[k * 2] >= 2.0 - 1e-9 && v[k * 2 + 1] >= 2.0 - 1e-9);
+        }
+    }
+
+    // 网格中只返回有路径的瓦片，只在边界上接触的瓦片没有路径
+    let grid = TileGrid {
+        origin: [0.0, 0.0],
+        tile_size: [10.0, 10.0],
+        columns: 3,
+        rows: 2,
+    };
+    let band = polygon(&[[-5.0, 2.0], [15.0, 2.0], [15.0, 8.0], [-5.0, 8.0]]);
+    let tiles = clip_to_grid(&band, &grid, true);
+    assert_eq!(tiles.len(), 2);
+    assert_eq!((tiles[0].column, tiles[0].row), (0, 0));
+    assert_eq!((tiles[1].column, tiles[1].row), (1, 0));
+    assert!((area(&tiles[0].paths[0]) - 60.0).abs() < 1e-9);
+    assert!((area(&tiles[1].paths[0]) - 30.0).abs() < 1e-9);
+    let tile = polygon(&[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]);
+    let tiles = clip_to_grid(&tile, &grid, true);
+    assert_eq!(tiles.len(), 1);
+    assert_eq!(tiles[0].paths, vec![tile]);
+
+    // 先按列再按行裁剪，每个瓦片与单独裁剪到瓦片矩形的结果相同
+    let blob = polygon(&[[-2.0, 3.0], [25.0, 1.0], [18.0, 17.0], [4.0, 14.0]]);
+    let tiles = clip_to_grid(&blob, &grid, true);
+    assert_eq!(tiles.len(), 6);
+    for tile in tiles {
+        let rect = grid.tile_rect(tile.column, tile.row);
+        assert_eq!(tile.paths, clip_to_rect(&blob, rect, true));
+    }
+}
+
+/// 测试射线和无限长直线与路径的交点
+#[test]
+fn test_ray_intersections() {
+    use intersections::{first_hit, intersect_infinite_line, intersect_ray};
+    let line = |x0: f64, y0: f64, x1: f64, y1: f64| [x0, y0, x0, y0, x1, y1, x1, y1];
+    let square = [
+        line(0.0, 0.0, 10.0, 0.0),
+        line(10.0, 0.0, 10.0, 10.0),
+        line(10.0, 10.0, 0.0, 10.0),
+        line(0.0, 10.0, 0.0, 0.0),
+    ];
+    let points = |hits: &[intersections::RayHit]| -> Vec<([f64; 2], f64)> {
+        hits.iter()
+            .map(|hit| {
+                let round = |v: f64| (v * 1e6).round() / 1e6;
+                (
+                    [round(hit.point[0]), round(hit.point[1])],
+                    round(hit.distance),
+                )
+            })
+            .collect()
+    };
+
+    let hits = intersect_ray([-5.0, 5.0], [2.0, 0.0], &square);
+    assert_eq!(points(&hits), vec![([0.0, 5.0], 5.0), ([10.0, 5.0], 15.0)]);
+    assert_eq!((hits[0].curve, hits[1].curve), (3, 1));
+    // 经过相邻曲线的连接点时只保留一个交点
+    let hits = intersect_ray([-5.0, -5.0], [1.0, 1.0], &square);
+    assert_eq!(hits.len(), 2);
+    assert!((hits[1].distance - 15.0 * 2f64.sqrt()).abs() < 1e-9);
+    // 不同曲线在内部经过同一点时各自保留
+    let cross = [line(0.0, 0.0, 10.0, 10.0), line(0.0, 10.0, 10.0, 0.0)];
+    let hits = intersect_ray([5.0, -5.0], [0.0, 1.0], &cross);
+    assert_eq!(points(&hits), vec![([5.0, 5.0], 10.0), ([5.0, 5.0], 10.0)]);
+    assert_eq!((hits[0].curve, hits[1].curve), (0, 1));
+    // 与射线重合的曲线返回两个端点
+    let hits = intersect_ray([-5.0, 0.0], [1.0, 0.0], &square);
+    assert_eq!(points(&hits), vec![([0.0, 0.0], 5.0), ([10.0, 0.0], 15.0)]);
+    // 射线背向路径
+    assert!(intersect_ray([-5.0, 5.0], [-1.0, 0.0], &square).is_empty());
+    assert!(intersect_ray([-5.0, 5.0], [0.0, 0.0], &square).is_empty());
+
+    let hits = intersect_infinite_line([5.0, 5.0], [1.0, 0.0], &square);
+    assert_eq!(points(&hits), vec![([0.0, 5.0], -5.0), ([10.0, 5.0], 5.0)]);
+
+    let arch: [f64; 8] = [0.0, 0.0, 0.0, 10.0, 10.0, 10.0, 10.0, 0.0];
+    let hits = intersect_ray([5.0, 20.0], [0.0, -1.0], &[arch]);
+    assert_eq!(points(&hits), vec![([5.0, 7.5], 12.5)]);
+    assert!((hits[0].t - 0.5).abs() < 1e-9);
+
+    // 第一个交点与全部交点中最近的一个相同
+    let mut curves = square.to_vec();
+    curves.push(arch);
+    for (origin, direction) in [
+        ([5.0, 5.0], [0.0, 1.0]),
+        ([5.0, 5.0], [1.0, 0.3]),
+        ([5.0, 20.0], [0.0, -1.0]),
+        ([-5.0, 5.0], [1.0, 0.0]),
+        ([20.0, 20.0], [1.0, 1.0]),
+    ] {
+        assert_eq!(
+            first_hit(origin, direction, &curves),
+            intersect_ray(origin, direction, &curves).first().copied()
+        );
+    }
+    assert_eq!(
+        first_hit([5.0, 20.0], [0.0, -1.0], &curves).map(|hit| hit.curve),
+        Some(2)
+    );
+}
+
+/// 测试扫描线交点、填充区间以及平行线填充
+#[test]
+fn test_scanline() {
+    use intersections::{hatch_lines, FillRule, Scanline};
+    let line = |x0: f64, y0: f64, x1: f64, y1: f64| [x0, y0, x0, y0, x1, y1, x1, y1];
+    let rect = |x0: f64, y0: f64, x1: f64, y1: f64| {
+        vec![
+            line(x0, y0, x1, y0),
+            line(x1, y0, x1, y1),
+            line(x1, y1, x0, y1),
+            line(x0, y1, x0, y0),
+        ]
+    };
+    let square = rect(0.0, 0.0, 10.0, 10.0);
+    let scanline = Scanline::new(&square);
+    let crossings = scanline.crossings(5.0);
+    let summary: Vec<(f64, i8, u32)> = crossings
+        .iter()
+        .map(|c| (c.x, c.winding, c.curve))
+        .collect();
+    assert_eq!(summary, vec![(0.0, -1, 3), (10.0, 1, 1)]);
+    // 只计入 [min, max) 内的交点
+    assert_eq!(scanline.crossings(0.0).len(), 2);
+    assert!(scanline.crossings(10.0).is_empty());
+    assert_eq!(scanline.spans(5.0, FillRule::NonZero), vec![[0.0, 10.0]]);
+
+    // 曲线组成的圆，片段连接点只算一次，经过 y 方向的极值点时环绕数不变
+    let circle: Vec<[f64; 8]> = vec![
+        [10.0, 5.0, 10.0, 7.76, 7.76, 10.0, 5.0, 10.0],
+        [5.0, 10.0, 2.24, 10.0, 0.0, 7.76, 0.0, 5.0],
+        [0.0, 5.0, 0.0, 2.24, 2.24, 0.0, 5.0, 0.0],
+        [5.0, 0.0, 7.76, 0.0, 10.0, 2.24, 10.0, 5.0],
+    ];
+    let scanline = Scanline::new(&circle);
+    let crossings = scanline.crossings(5.0);
+    assert_eq!(crossings.len(), 2);
+    assert_eq!((crossings[0].x, crossings[1].x), (0.0, 10.0));
+    let crossings = scanline.crossings(2.0);
+    assert_eq!(crossings.len(), 2);
+    assert!((crossings[0].x - 1.0).abs() < 0.01 && (crossings[1].x - 9.0).abs() < 0.01);
+    for c in &crossings {
+        let p = intersections::evaluate(&circle[c.curve as usize], c.t, 0).unwrap();
+        assert!((p[0] - c.x).abs() < 1e-9 && (p[1] - 2.0).abs() < 1e-9);
+    }
+    let top = scanline.crossings(0.0);
+    assert_eq!(top.iter().map(|c| c.winding as i32).sum::<i32>(), 0);
+    assert!(scanline.spans(0.0, FillRule::NonZero).is_empty());
+
+    // 两个方向相同的矩形重叠的部分在奇偶规则下不填充
+    let mut overlap = rect(0.0, 0.0, 10.0, 10.0);
+    overlap.extend(rect(5.0, 0.0, 15.0, 10.0));
+    let scanline = Scanline::new(&overlap);
+    assert_eq!(scanline.spans(5.0, FillRule::NonZero), vec![[0.0, 15.0]]);
+    assert_eq!(
+        scanline.spans(5.0, FillRule::EvenOdd),
+        vec![[0.0, 5.0], [10.0, 15.0]]
+    );
+
+    let lines = hatch_lines(&square, 2.5, 0.0, FillRule::NonZero);
+    assert_eq!(
+        lines,
+        vec![
+            [0.0, 0.0, 10.0, 0.0],
+            [0.0, 2.5, 10.0, 2.5],
+            [0.0, 5.0, 10.0, 5.0],
+            [0.0, 7.5, 10.0, 7.5]
+        ]
+    );
+    // 不闭合的路径按直线闭合
+    assert_eq!(
+        hatch_lines(&square[..3], 2.5, 0.0, FillRule::NonZero),
+        lines
+    );
+    // 竖直的线，边界不落在线上避免旋转的舍入误差
+    let inner = rect(1.0, 1.0, 9.0, 9.0);
+    let lines = hatch_lines(&inner, 2.5, std::f64::consts::FRAC_PI_2, FillRule::NonZero);
+    assert_eq!(lines.len(), 3);
+    for (l, x) in lines.iter().zip([7.5, 5.0, 2.5]) {
+        assert!((l[0] - x).abs() < 1e-9 && (l[2] - x).abs() < 1e-9);
+        assert!(((l[1] - l[3]).abs() - 8.0).abs() < 1e-9);
+    }
+    // 斜线的总长度约为面积除以间距
+    let lines = hatch_lines(&square, 0.5, std::f64::consts::FRAC_PI_4, FillRule::NonZero);
+    let length: f64 = lines.iter().map(|l| (l[2] - l[0]).hypot(l[3] - l[1])).sum();
+    assert!((length - 200.0).abs() < 5.0, "{length}");
+    for l in &lines {
+        for p in [[l[0], l[1]], [l[2], l[3]]] {
+            assert!(p.iter().all(|&v| (-1e-9..=10.0 + 1e-9).contains(&v)));
+        }
+    }
+    assert!(hatch_lines(&square, 0.0, 0.0, FillRule::NonZero).is_empty());
+
+    // 间距过小、路径坐标为无穷大或线的序号超出整数精度时直接返回空，不会一直循环
+    assert!(hatch_lines(&square, 1e-9, 0.0, FillRule::NonZero).is_empty());
+    assert!(hatch_lines(&square, f64::INFINITY, 0.0, FillRule::NonZero).is_empty());
+    let mut infinite = square.clone();
+    infinite[0][1] = f64::NEG_INFINITY;
+    assert!(hatch_lines(&infinite, 2.5, 0.0, FillRule::NonZero).is_empty());
+    let far: Vec<[f64; 8]> = square.iter().map(|v| v.map(|x| x + 1e20)).collect();
+    assert!(hatch_lines(&far, 2.5, 0.0, FillRule::NonZero).is_empty());
+    // 线条数量比上限多一条
+    let spacing = 10.0 / intersections::MAX_HATCH_LINES as f64;
+    assert!(hatch_lines(&square, spacing, 0.0, FillRule::NonZero).is_empty());
+    assert!(!hatch_lines(&square, spacing * 64.0, 0.0, FillRule::NonZero).is_empty());
+}
+
+/// 测试按列存放的结果缓冲区
+#[cfg(feature = "wasm")]
+#[test]
+fn test_intersection_buffer() {
+    let curves: Vec<[f64; 8]> = vec![
+        [38.5, 0.0, 38.5, 0.0, 62.0, 87.0, 62.0, 87.0],
+        [0.0, 64.5, 0.0, 64.5, 80.0, 17.5, 80.0, 17.5],
+        [80.0, 17.5, 80.0, 17.5, 0.0, 17.5, 0.0, 17.5],
+        [0.0, 17.5, 0.0, 17.5, 78.5, 67.0, 78.5, 67.0],
+        [78.5, 67.0, 78.5, 67.0, 0.0, 64.5, 0.0, 64.5],
+    ];
+    let slice: Vec<f64> = curves.concat();
+    let mut buffer = intersections::wasm::IntersectionBuffer::new();
+    for _ in 0..2 {
+        buffer.intersect_self(&slice, None);
+        let mut locations = vec![];
+        intersections::get_intersections(&curves, &curves, true, &mut locations);
+        assert_eq!(buffer.length(), locations.len());
+        let columns = buffer.columns();
+        for (k, location) in locations.iter().enumerate() {
+            assert_eq!(columns.t1[k], location[0]);
+            assert_eq!(columns.curve1[k], location[1] as u32);
+            assert_eq!((columns.x[k], columns.y[k]), (location[2], location[3]));
+            assert_eq!(columns.t2[k], location[4]);
+            assert_eq!(columns.curve2[k], location[5] as u32);
+        }
+    }
+    // 复用缓冲区时覆盖上一次的结果
+    buffer.intersect(&slice[..8], &slice[8..16], None);
+    assert_eq!(buffer.length(), 1);
+    assert_eq!(buffer.columns().curve2, &[0]);
+}
+
+/// 测试调试 SVG 输出
+#[test]
+fn test_debug_svg() {
+    let curves1: Vec<[f64; 8]> = vec![
+        [0.0, 0.0, 0.0, 0.0, 100.0, 100.0, 100.0, 100.0],
+        [0.0, 80.0, 0.0, 80.0, 10.0, 85.0, 10.0, 85.0],
+    ];
+    let curves2: Vec<[f64; 8]> = vec![[0.0, 100.0, 0.0, 100.0, 100.0, 0.0, 100.0, 0.0]];
+    let mut locations = vec![];
+    intersections::get_intersections(&curves1, &curves2, false, &mut locations);
+    assert_eq!(locations.len(), 1);
+    let svg = intersections::render_debug_svg(&curves1, &curves2, false, &locations);
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+    assert!(svg.contains("viewBox=\"-5 -5 110 110\""));
+    assert_eq!(svg.matches("<path").count(), 3);
+    assert_eq!(svg.matches("<rect").count(), 3);
+    assert_eq!(svg.matches("<circle cx=\"50\" cy=\"50\"").count(), 1);
+    // 两个候选曲线对中只有第一对报告了相交点
+    assert_eq!(svg.matches("<line").count(), 2);
+    assert_eq!(svg.matches("stroke-dasharray=\"4 2\"").count(), 1);
+}
+
+/// 测试贝塞尔裁剪的跟踪记录
+#[test]
+fn test_clip_trace() {
+    use intersections::{ClipDecision, ClipStep, ClipTrace, EndpointPolicy};
+    let v1 = [0.0, 0.0, 100.0, 200.0, 200.0, -100.0, 300.0, 100.0];
+    let v2 = [0.0, 100.0, 100.0, -100.0, 200.0, 200.0, 310.0, 0.0];
+    let mut trace = ClipTrace::default();
+    let locations = intersections::get_curve_intersections_traced(
+        &v1,
+        &v2,
+        EndpointPolicy::default(),
+        &mut trace,
+    );
+    let mut expected = vec![];
+    intersections::get_intersections(&[v1], &[v2], false, &mut expected);
+    assert_eq!(locations, expected);
+    assert_eq!(locations.len(), 3);
+
+    let first = &trace.steps[0];
+    assert_eq!((first.call, first.recursion, first.flip), (1, 1, false));
+    assert_eq!((first.v1, first.v2), (v1, v2));
+    assert_eq!((first.t_range, first.u_range), ([0.0, 1.0], [0.0, 1.0]));
+    assert!(first.fatline.is_some() && first.hull.is_some() && first.clip.is_some());
+    let converged = trace
+        .steps
+        .iter()
+        .filter(|step| step.decision == ClipDecision::Converged { reported: true })
+        .count();
+    assert_eq!(converged, locations.len());
+    for step in &trace.steps {
+        if let Some([t_min_clip, t_max_clip]) = step.clip {
+            assert!(0.0 <= t_min_clip && t_min_clip <= t_max_clip && t_max_clip <= 1.0);
+        }
+    }
+
+    let mut count = 0;
+    intersections::get_curve_intersections_traced(
+        &v1,
+        &v2,
+        EndpointPolicy::default(),
+        &mut |_: &ClipStep| count += 1,
+    );
+    assert_eq!(count, trace.steps.len());
+
+    let json = trace.to_json();
+    assert!(json.starts_with("{\"steps\":[{\"call\":1,\"recursion\":1,\"flip\":false,"));
+    assert_eq!(json.matches("\"decision\":").count(), trace.steps.len());
+}
+
+/// 测试求交统计数据
+#[test]
+fn test_intersection_stats() {
+    use intersections::EndpointPolicy;
+    let curves: Vec<[f64; 8]> = vec![
+        [0.0, 50.0, 0.0, 50.0, 100.0, 50.0, 100.0, 50.0],
+        [50.0, 0.0, 50.0, 0.0, 50.0, 100.0, 50.0, 100.0],
+        [10.0, 0.0, 40.0, 150.0, 60.0, -50.0, 90.0, 100.0],
+        [0.0, 80.0, 30.0, -20.0, 70.0, 120.0, 100.0, 20.0],
+        // 与其它曲线的边界框都不相交
+        [500.0, 500.0, 510.0, 520.0, 530.0, 490.0, 540.0, 500.0],
+    ];
+    let mut locations = vec![];
+    let stats = intersections::get_intersections_with_stats(
+        &curves,
+        &curves,
+        true,
+        EndpointPolicy::default(),
+        &mut locations,
+    );
+    let mut expected = vec![];
+    intersections::get_intersections(&curves, &curves, true, &mut expected);
+    assert_eq!(locations, expected);
+
+    assert_eq!(stats.candidate_pairs, 6);
+    assert_eq!(stats.intersecting_pairs, 6);
+    assert_eq!(
+        (stats.line_line, stats.line_curve, stats.curve_curve),
+        (1, 4, 1)
+    );
+    assert!(stats.clip_calls > 0 && stats.clip_calls == stats.max_clip_calls);
+    assert!(stats.max_recursion > 0 && stats.total_recursion == stats.max_recursion);
+}
+
+#[test]
+fn test_budgeted_intersections() {
+    use intersections::{Deadline, EndpointPolicy, IntersectionJob, WorkBudget};
+    let curves: Vec<[f64; 8]> = vec![
+        [0.0, 50.0, 0.0, 50.0, 100.0, 50.0, 100.0, 50.0],
+        [50.0, 0.0, 50.0, 0.0, 50.0, 100.0, 50.0, 100.0],
+        [10.0, 0.0, 40.0, 150.0, 60.0, -50.0, 90.0, 100.0],
+        [0.0, 80.0, 30.0, -20.0, 70.0, 120.0, 100.0, 20.0],
+    ];
+    let mut expected = vec![];
+    let stats = intersections::get_intersections_with_stats(
+        &curves,
+        &curves,
+        true,
+        EndpointPolicy::default(),
+        &mut expected,
+    );
+
+    // 每次只完成少量工作，拼接各段结果和累计的统计数据应与一次完成相同
+    let mut locations = vec![];
+    let mut job = IntersectionJob::new(&curves, &curves, true, EndpointPolicy::default());
+    let mut chunks = 0;
+    loop {
+        chunks += 1;
+        let cursor = job.cursor().unwrap();
+        if job.resume(&curves, &curves, &mut WorkBudget::new(2), &mut locations) {
+            break;
+        }
+        assert!(job.cursor().unwrap() > cursor);
+    }
+    assert!(chunks > 1);
+    assert_eq!(locations, expected);
+    assert_eq!(job.stats(), stats);
+    assert!(job.resume(&curves, &curves, &mut (), &mut locations));
+    assert_eq!(locations, expected);
+
+    // 20×20 的网格共 400 个候选曲线对，每行的候选查询另记 1 个单位，
+    // 时钟每 256 个单位读取一次，已经超时则立即暂停
+    let rows: Vec<[f64; 8]> = (0..20)
+        .map(|i| {
+            let y = i as f64 * 5.0;
+            [0.0, y, 0.0, y, 100.0, y, 100.0, y]
+        })
+        .collect();
+    let columns: Vec<[f64; 8]> = (0..20)
+        .map(|i| {
+            let x = i as f64 * 5.0 + 2.5;
+            [x, -1.0, x, -1.0, x, 101.0, x, 101.0]
+        })
+        .collect();
+    let mut ticks = 0;
+    let mut deadline = Deadline::new(
+        || {
+            ticks += 1;
+            1.0
+        },
+        0.0,
+    );
+    let mut partial = vec![];
+    let mut job = IntersectionJob::new(&rows, &columns, false, EndpointPolicy::default());
+    assert!(!job.resume(&rows, &columns, &mut deadline, &mut partial));
+    assert_eq!(ticks, 1);
+    assert_eq!(partial.len(), 12 * 20 + 3);
+    assert!(job.resume(&rows, &columns, &mut (), &mut partial));
+    assert_eq!(job.cursor(), None);
+    assert_eq!(partial.len(), 400);
+    assert_eq!(job.stats().candidate_pairs, 400);
+}
+
+#[test]
+fn test_self_intersection() {
+    use intersections::{get_self_intersection, EndpointPolicy, SelfIntersection};
+    let eval = |v: &[f64; 8], t: f64| intersections::evaluate(v, t, 0).unwrap();
+    let assert_close = |a: [f64; 2], b: [f64; 2]| {
+        assert!(
+            (a[0] - b[0]).abs() < 1e-7 && (a[1] - b[1]).abs() < 1e-7,
+            "{a:?} {b:?}"
+        );
+    };
+
+    // 对称的环，手柄交叉超过尖点的位置
+    let looped: [f64; 8] = [0.0, 0.0, 150.0, 100.0, -50.0, 100.0, 100.0, 0.0];
+    let SelfIntersection::Loop { t1, t2 } = get_self_intersection(&looped) else {
+        panic!("应为环");
+    };
+    assert!(0.0 < t1 && t1 < 0.5 && 0.5 < t2 && t2 < 1.0);
+    assert!((t1 + t2 - 1.0).abs() < 1e-12);
+    assert_close(eval(&looped, t1), eval(&looped, t2));
+
+    // 从环的交点处切开，起点再次被经过
+    let tail = intersections::split_cubic_bezier_part(&looped, t1, 1.0);
+    let SelfIntersection::EndpointLoop { t1, t2 } = get_self_intersection(&tail) else {
+        panic!("应为端点处闭合的环");
+    };
+    assert!(t1 == 0.0 && t2 < 1.0);
+    assert_close(eval(&tail, 0.0), eval(&tail, t2));
+
+    // 首尾相接的环
+    let closed = [0.0, 0.0, 100.0, 100.0, -100.0, 100.0, 0.0, 0.0];
+    assert_eq!(
+        get_self_intersection(&closed),
+        SelfIntersection::EndpointLoop { t1: 0.0, t2: 1.0 }
+    );
+
+    // 尖点，以及与尖点相差很小的环
+    let cusp = [0.0, 0.0, 100.0, 100.0, 0.0, 100.0, 100.0, 0.0];
+    assert_eq!(
+        get_self_intersection(&cusp),
+        SelfIntersection::Cusp { t: 0.5 }
+    );
+    // 接近尖点时环很小但仍能求出，更接近时归为尖点
+    let near_cusp = [0.0, 0.0, 100.0 + 1e-9, 100.0, -1e-9, 100.0, 100.0, 0.0];
+    let SelfIntersection::Loop { t1, t2 } = get_self_intersection(&near_cusp) else {
+        panic!("应为环");
+    };
+    assert!(t2 - t1 < 1e-5);
+    assert_close(eval(&near_cusp, t1), eval(&near_cusp, t2));
+    let near_cusp = [0.0, 0.0, 100.0 + 1e-12, 100.0, -1e-12, 100.0, 100.0, 0.0];
+    assert!(matches!(
+        get_self_intersection(&near_cusp),
+        SelfIntersection::Cusp { .. }
+    ));
+
+    // 退化输入
+    for v in [
+        [0.0, 0.0, 0.0, 0.0, 100.0, 100.0, 100.0, 100.0],
+        [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 100.0, 0.0, -50.0, 0.0, 50.0, 0.0],
+        // 长度为零的手柄在端点处形成的尖点
+        [0.0, 0.0, 0.0, 0.0, 50.0, 100.0, 100.0, 0.0],
+        // 两个根都在曲线之外的环
+        [0.0, 0.0, 40.0, 100.0, 60.0, 100.0, 100.0, 0.0],
+        [0.0, 0.0, 50.0, 100.0, 50.0, -100.0, 100.0, 0.0],
+    ] {
+        assert_eq!(get_self_intersection(&v), SelfIntersection::None, "{v:?}");
+    }
+
+    // 求交结果不包括尖点，首尾相接的环受端点策略约束
+    let mut locations = vec![];
+    intersections::get_intersections(&[cusp, closed], &[cusp, closed], true, &mut locations);
+    assert!(locations.is_empty());
+    intersections::get_intersections(&[looped], &[looped], true, &mut locations);
+    assert_eq!(locations.len(), 1);
+    let mut locations = vec![];
+    intersections::get_intersections_with_policy(
+        &[closed],
+        &[closed],
+        true,
+        EndpointPolicy::IncludeAll,
+        &mut locations,
+    );
+    assert_eq!(locations.len(), 1);
+    assert_eq!((locations[0][0], locations[0][4]), (0.0, 1.0));
+}
+
+/// 测试提交的 C 头文件与 cbindgen 生成的结果一致
+#[cfg(feature = "ffi")]
+#[test]
+fn test_ffi_header() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/intersections.h"));
+    let committed = include_str!("../include/intersections.h");
+    assert!(
+        generated == committed,
+        "include/intersections.h 已过期，运行 INTERSECTIONS_UPDATE_HEADER=1 cargo build --features ffi 更新"
+    );
+}